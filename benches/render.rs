@@ -0,0 +1,158 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::Text,
+    widgets::{Block, Borders, Paragraph, StatefulWidget, Widget, Wrap},
+};
+use std::hint::black_box;
+use valentines_rs::background::{BackgroundCache, BackgroundKind};
+use valentines_rs::{App, Card, CardWidget, CurrentScreen, Heart3d};
+
+// about the top half of a 160x50 terminal
+const AREA: Rect = Rect::new(0, 0, 156, 20);
+const SCREEN: Rect = Rect::new(0, 0, 160, 50);
+
+const KINDS: [BackgroundKind; 6] = [
+    BackgroundKind::Marquee,
+    BackgroundKind::HeartRain,
+    BackgroundKind::Stars,
+    BackgroundKind::Bubbles,
+    BackgroundKind::Waves,
+    BackgroundKind::Shapes,
+];
+
+// How every screen drew its background before there were generators: a new
+// 20 KB string each frame, wrapped all over again.
+fn legacy_background(area: Rect, buf: &mut Buffer, offset: u8) {
+    let my_string = match offset {
+        0 => "My Valentine My Lovely Lady My Love <3 ".repeat(500),
+        1 => "My Lovely Lady My Love <3 ".repeat(500),
+        2 => "My Love <3 ".repeat(500),
+        3 => " <3 ".repeat(900),
+        _ => "My Valentine My Lovely Lady My Love <3 ".repeat(500),
+    };
+    let valentines_background = Paragraph::new(Text::from(my_string))
+        .wrap(Wrap { trim: false })
+        .style(
+            Style::default()
+                .fg(Color::Magenta)
+                .bg(Color::Black)
+                .add_modifier(Modifier::ITALIC | Modifier::BOLD),
+        )
+        .centered()
+        .block(Block::default().borders(Borders::ALL).title(""))
+        .alignment(Alignment::Center);
+    valentines_background.render(area, buf);
+}
+
+fn backgrounds(c: &mut Criterion) {
+    let mut group = c.benchmark_group("background");
+    let mut buf = Buffer::empty(AREA);
+    group.bench_function("legacy_paragraph", |b| {
+        b.iter(|| legacy_background(AREA, &mut buf, black_box(0)))
+    });
+    for kind in KINDS {
+        // a new frame every time, as when the animation moves on
+        let background = kind.background();
+        let mut tick = 0;
+        group.bench_function(format!("{kind:?}/uncached"), |b| {
+            b.iter(|| {
+                tick += 1;
+                background.render(AREA, &mut buf, black_box(tick))
+            })
+        });
+        // the same frame again, as when a key is pressed between ticks
+        let mut cache = BackgroundCache::default();
+        group.bench_function(format!("{kind:?}/cached"), |b| {
+            b.iter(|| cache.render(kind, AREA, &mut buf, black_box(40)))
+        });
+    }
+    group.finish();
+}
+
+fn card(screen: CurrentScreen, paused: bool) -> App {
+    let mut app = App::new(Card::default());
+    app.current_screen = screen;
+    app.paused = paused;
+    app
+}
+
+fn frames(c: &mut Criterion) {
+    let mut group = c.benchmark_group("card");
+    let mut buf = Buffer::empty(SCREEN);
+    let mut app = card(CurrentScreen::DateInfo, false);
+    group.bench_function("frame", |b| {
+        b.iter(|| {
+            app.on_tick();
+            CardWidget.render(SCREEN, &mut buf, &mut app)
+        })
+    });
+    // ten turns of the main loop with the card paused, the way it was,
+    // drawing every time
+    let mut app = card(CurrentScreen::DateInfo, true);
+    group.bench_function("paused_loop/always_draw", |b| {
+        b.iter(|| {
+            for _ in 0..10 {
+                CardWidget.render(SCREEN, &mut buf, &mut app);
+                app.on_tick();
+            }
+        })
+    });
+    // and only drawing when something changed
+    let mut app = card(CurrentScreen::DateInfo, true);
+    group.bench_function("paused_loop/when_dirty", |b| {
+        b.iter(|| {
+            for _ in 0..10 {
+                if app.dirty {
+                    CardWidget.render(SCREEN, &mut buf, &mut app);
+                }
+                app.on_tick();
+            }
+        })
+    });
+    // running, on a screen where nothing moves but the clock
+    let mut app = card(CurrentScreen::IntroScreen, false);
+    group.bench_function("still_loop/always_draw", |b| {
+        b.iter(|| {
+            for _ in 0..10 {
+                CardWidget.render(SCREEN, &mut buf, &mut app);
+                app.on_tick();
+            }
+        })
+    });
+    let mut app = card(CurrentScreen::IntroScreen, false);
+    group.bench_function("still_loop/when_dirty", |b| {
+        b.iter(|| {
+            for _ in 0..10 {
+                if app.dirty {
+                    CardWidget.render(SCREEN, &mut buf, &mut app);
+                }
+                app.on_tick();
+            }
+        })
+    });
+    group.finish();
+}
+
+// The spinning heart in the playground of an 80x24 and a 160x50 terminal, and
+// filling a whole big one. Anything under TICK_RATE keeps up.
+fn heart3d(c: &mut Criterion) {
+    let mut group = c.benchmark_group("heart3d");
+    let heart = Heart3d::default();
+    for area in [Rect::new(0, 0, 78, 10), Rect::new(0, 0, 158, 23), SCREEN] {
+        let mut buf = Buffer::empty(area);
+        let mut tick = 0;
+        group.bench_function(format!("{}x{}", area.width, area.height), |b| {
+            b.iter(|| {
+                tick += 1;
+                heart.render(area, &mut buf, black_box(tick))
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, backgrounds, frames, heart3d);
+criterion_main!(benches);
@@ -0,0 +1,56 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::seal::Sealed;
+
+// One cell per day from `start` up to the date itself. `days` fills the cells
+// in order; cells without an entry just get a heart.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Advent {
+    #[serde(with = "crate::card::wall_clock::date")]
+    pub start: NaiveDate,
+    // defaults to the day of the first date plan
+    #[serde(default, with = "crate::card::wall_clock::date_option")]
+    pub end: Option<NaiveDate>,
+    #[serde(default)]
+    pub days: Vec<Surprise>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Surprise {
+    #[serde(default)]
+    pub kind: SurpriseKind,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub text: Sealed,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SurpriseKind {
+    #[default]
+    Message,
+    Poem,
+    Animation,
+}
+
+// more cells than this wouldn't fit on any screen
+const MAX_DAYS: usize = 62;
+
+impl Advent {
+    pub fn days(&self, fallback_end: NaiveDate) -> Vec<NaiveDate> {
+        let end = self.end.unwrap_or(fallback_end);
+        self.start
+            .iter_days()
+            .take_while(|day| *day <= end)
+            .take(MAX_DAYS)
+            .collect()
+    }
+
+    pub fn surprise(&self, index: usize) -> Option<&Surprise> {
+        self.days.get(index)
+    }
+}
@@ -0,0 +1,110 @@
+use chrono::{Datelike, Months, NaiveDate};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// How long we've been together, counted from `since`. Messages are keyed by
+// milestone label ("100 days", "6 months", "1 year") and shown on that day.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Anniversary {
+    #[serde(with = "crate::card::wall_clock::date")]
+    pub since: NaiveDate,
+    #[serde(default)]
+    pub messages: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Milestone {
+    pub date: NaiveDate,
+    pub label: String,
+}
+
+impl Anniversary {
+    pub fn days_together(&self, today: NaiveDate) -> i64 {
+        (today - self.since).num_days()
+    }
+
+    // Whole years, months and days since `since`, like counting on a calendar.
+    pub fn together(&self, today: NaiveDate) -> (u32, u32, u32) {
+        let months = self.months_together(today);
+        let last_monthiversary = self.after_months(months);
+        let days = (today - last_monthiversary).num_days().max(0) as u32;
+        (months / 12, months % 12, days)
+    }
+
+    // The next 100 day, monthly and yearly milestones, soonest first. Today
+    // counts as upcoming so the day itself still shows up.
+    pub fn upcoming(&self, today: NaiveDate) -> Vec<Milestone> {
+        let days = self.days_together(today).max(0);
+        let hundreds = (days + 99) / 100;
+        let mut months = self.months_together(today);
+        if self.after_months(months) < today {
+            months += 1;
+        }
+        let mut months_to_next_year = months.div_ceil(12) * 12;
+        if months_to_next_year == 0 {
+            months_to_next_year = 12;
+        }
+        // a monthiversary that lands on a year is shown as the year instead
+        if months % 12 == 0 {
+            months += 1;
+        }
+
+        let mut upcoming = vec![
+            Milestone {
+                date: self.since + chrono::Duration::days(hundreds.max(1) * 100),
+                label: format!("{} days", hundreds.max(1) * 100),
+            },
+            Milestone {
+                date: self.after_months(months.max(1)),
+                label: plural(months.max(1), "month"),
+            },
+            Milestone {
+                date: self.after_months(months_to_next_year),
+                label: plural(months_to_next_year / 12, "year"),
+            },
+        ];
+        upcoming.sort_by_key(|milestone| milestone.date);
+        upcoming
+    }
+
+    pub fn milestones_on(&self, today: NaiveDate) -> Vec<Milestone> {
+        self.upcoming(today)
+            .into_iter()
+            .filter(|milestone| milestone.date == today)
+            .collect()
+    }
+
+    pub fn message_for(&self, milestone: &Milestone) -> String {
+        self.messages
+            .get(&milestone.label)
+            .cloned()
+            .unwrap_or_else(|| format!("Happy {}, my love!", milestone.label))
+    }
+
+    fn months_together(&self, today: NaiveDate) -> u32 {
+        if today < self.since {
+            return 0;
+        }
+        let mut months = (today.year() - self.since.year()) as u32 * 12 + today.month()
+            - self.since.month();
+        if self.after_months(months) > today {
+            months -= 1;
+        }
+        months
+    }
+
+    fn after_months(&self, months: u32) -> NaiveDate {
+        self.since
+            .checked_add_months(Months::new(months))
+            .unwrap_or(NaiveDate::MAX)
+    }
+}
+
+fn plural(count: u32, unit: &str) -> String {
+    if count == 1 {
+        format!("1 {unit}")
+    } else {
+        format!("{count} {unit}s")
+    }
+}
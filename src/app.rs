@@ -1,257 +1,837 @@
-use crate::ui::ui;
-use crossterm::event::{self, Event, KeyCode, KeyEvent};
+use crate::background::BackgroundCache;
+use crate::card::{Card, DatePlan, EventStatus};
+use crate::clock::Clock;
+use crate::heart::Bounce;
+use crate::input::{TextInput, TextInputState};
+use crate::keymap::Action;
+use crate::scene::{self, Scene, SceneId, Scenes};
+use crate::state::SavedState;
+use crate::transition::{Effect, Transition};
+use crate::widget::CardWidget;
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind,
+};
+use serde::{Deserialize, Serialize};
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style, Stylize},
-    symbols::Marker,
-    widgets::{
-        canvas::{Canvas, Circle, Painter, Shape},
-        Block, Borders, Clear, List, ListItem, Paragraph, Widget, Wrap,
-    },
+    buffer::Buffer,
+    layout::{Position, Rect},
+    widgets::{ListState, Widget},
     DefaultTerminal,
 };
 use std::{
-    borrow::BorrowMut,
-    fmt, io,
+    io,
+    path::PathBuf,
+    rc::Rc,
     time::{Duration, Instant},
 };
-#[derive(Debug, Default, Clone)]
-pub struct Heart {
-    pub x: f64,
-    pub y: f64,
-    pub radius: f64,
-    pub color: Color,
-}
+// how often App::on_tick wants calling, which everything that moves is
+// paced for
+pub const TICK_RATE: Duration = Duration::from_millis(100);
+const CELEBRATION_TICKS: u64 = ticks(Duration::from_secs(8));
+// going back further than this isn't worth keeping in the state file
+const MAX_HISTORY: usize = 64;
 
-impl Shape for Heart {
-    fn draw(&self, painter: &mut Painter<'_, '_>) {
-        // Iterate over the parameter t to draw the heart
-        for t in (0..=360).map(|i| i as f64 * (std::f64::consts::PI / 180.0)) {
-            let heart_x = 16.0 * t.sin().powi(3);
-            let heart_y =
-                13.0 * t.cos() - 5.0 * (2.0 * t).cos() - 2.0 * (3.0 * t).cos() - (4.0 * t).cos();
-
-            // Scale and translate the heart
-            let x = self.radius.mul_add(heart_x, self.x);
-            let y = self.radius.mul_add(heart_y, self.y); // Negate y to flip it upright
-
-            // Paint the point if it's within the canvas bounds
-            if let Some((x, y)) = painter.get_point(x, y) {
-                painter.paint(x, y, self.color);
-            }
-        }
-    }
+// How many ticks `duration` takes.
+pub const fn ticks(duration: Duration) -> u64 {
+    (duration.as_millis() / TICK_RATE.as_millis()) as u64
 }
+
 #[derive(Debug, Default)]
-pub struct App<'a> {
-    pub title: &'a str,
+pub struct App {
+    pub title: &'static str,
     pub exit: bool,
     pub current_screen: CurrentScreen,
     pub intro_screen: IntroScreenOptions,
-    heart: Heart,
+    pub card: Card,
+    pub clock: Clock,
+    pub answer: Option<Answer>,
+    pub rsvp_input: TextInput,
+    pub rsvp_input_state: TextInputState,
+    pub gate_input: TextInput,
+    pub gate_input_state: TextInputState,
+    pub plan_list: ListState,
+    pub plan_list_area: Rect,
+    pub itinerary_list: ListState,
+    pub letter_list: ListState,
+    pub saved: SavedState,
+    pub show_help: bool,
+    // stops the animations, the clock keeps going
+    pub paused: bool,
+    state_path: Option<PathBuf>,
+    // where Backspace goes, most recent last
+    history: Vec<Visit>,
+    pub advent_cursor: usize,
+    // the advent cell whose surprise is showing
+    pub advent_open: Option<usize>,
+    scenes: Scenes,
+    // the screen whose scene was last told it was entered
+    entered: Option<CurrentScreen>,
+    // what was drawn last, for the next transition to start from
+    pub last_frame: Buffer,
+    pub transition: Option<Transition>,
+    pub background_cache: BackgroundCache,
+    // something on screen has changed since the last draw
+    pub dirty: bool,
+    // the clock's second when it was last drawn
+    pub drawn_second: i64,
+    // true while the chosen plan is still ahead of us
+    countdown_pending: bool,
+    pub celebrate_until: u64,
+    pub bounce: Bounce,
     pub playground: Rect,
-    vx: f64,
-    vy: f64,
-    tick_count: u64,
+    // where the terminal cursor goes while something is being typed, as of
+    // the last draw
+    pub cursor: Option<Position>,
+    pub tick_count: u64,
     pub offset: u8,
-    marker: Marker,
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CurrentScreen {
+    Gate,
     IntroScreen,
+    Advent,
+    #[default]
     WillYouBeMyValentine,
     NotValentine,
     YesValentine,
+    Rsvp,
+    DateChoice,
     DateInfo,
+    Itinerary,
+    Anniversary,
+    Letters,
+    Celebration,
+    // a scene added with App::with_scene, saved under its own name
+    #[serde(untagged)]
+    Custom(SceneId),
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum IntroScreenOptions {
+    #[default]
     Screen1, // as you might know, there's a question i've been meaning to ask
     Screen2, // i'm sorry it took longer than i expected, this took a while!
     Screen3, // You've waited long enough my pretty lady, I have a question...
 }
 
-impl fmt::Debug for IntroScreenOptions {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
-    }
-}
-
-impl Default for IntroScreenOptions {
-    fn default() -> Self {
-        IntroScreenOptions::Screen1
-    }
+// A place in the card to go back to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Visit {
+    pub screen: CurrentScreen,
+    pub intro_screen: IntroScreenOptions,
 }
 
-impl fmt::Debug for CurrentScreen {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
-    }
-}
-impl Default for CurrentScreen {
-    fn default() -> Self {
-        CurrentScreen::WillYouBeMyValentine
-    }
+// What the recipient answered, along with the note typed on the RSVP screen
+// and the index of the date plan they picked.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Answer {
+    pub accepted: bool,
+    pub note: String,
+    pub plan: Option<usize>,
 }
 
-impl<'a> App<'a> {
-    pub fn new() -> Self {
-        Self {
-            title: "To My Valentie",
+impl App {
+    pub fn new(card: Card) -> Self {
+        let mut bounce = Bounce {
+            sprite: card.bouncing.clone(),
+            trail: card.trail,
+            beating: card.heart.beating,
+            ..Bounce::default()
+        };
+        bounce.heart.fill = card.heart.fill;
+        scene::built_in(Self {
+            title: "To My Valentine...",
             exit: false,
-            current_screen: CurrentScreen::IntroScreen,
-            intro_screen : IntroScreenOptions::Screen1,
-            heart: Heart {
-                x: 5.0,
-                y: 5.0,
-                radius: 0.5,
-                color: Color::LightMagenta,
+            current_screen: if card.gate.is_some() {
+                CurrentScreen::Gate
+            } else {
+                CurrentScreen::IntroScreen
             },
+            intro_screen : IntroScreenOptions::Screen1,
+            card,
+            clock: Clock::System,
+            answer: None,
+            rsvp_input: TextInput::new().with_max_len(280),
+            rsvp_input_state: TextInputState::default(),
+            gate_input: TextInput::new().with_max_len(200),
+            gate_input_state: TextInputState::default(),
+            plan_list: ListState::default().with_selected(Some(0)),
+            plan_list_area: Rect::default(),
+            itinerary_list: ListState::default(),
+            letter_list: ListState::default().with_selected(Some(0)),
+            saved: SavedState::default(),
+            show_help: false,
+            paused: false,
+            state_path: None,
+            history: Vec::new(),
+            advent_cursor: 0,
+            advent_open: None,
+            scenes: Scenes::default(),
+            entered: None,
+            last_frame: Buffer::default(),
+            transition: None,
+            background_cache: BackgroundCache::default(),
+            dirty: true,
+            drawn_second: 0,
+            countdown_pending: false,
+            celebrate_until: 0,
+            bounce,
             playground: Rect::new(0, 0, 200, 100),
-            vx: 3.0,
-            vy: 3.0,
+            cursor: None,
             offset: 0,
             tick_count: 0,
-            marker: Marker::Braille,
+        })
+    }
+
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    // Adds a scene, or swaps out a built in one. Anything can take the card
+    // to it with go_to.
+    pub fn with_scene(mut self, screen: CurrentScreen, scene: impl Scene + 'static) -> Self {
+        self.scenes.register(screen, scene);
+        self
+    }
+
+    // Picks up where the last run left off. With a gate the question still
+    // comes first, and the rest resumes once it's answered.
+    pub fn with_state(mut self, path: PathBuf, saved: SavedState) -> Self {
+        self.state_path = Some(path);
+        self.intro_screen = saved.intro_screen;
+        self.answer = saved.answer.clone();
+        self.history = saved.history.clone();
+        if let Some(answer) = &mut self.answer {
+            answer.plan = answer.plan.filter(|plan| *plan < self.card.plans.len());
+            self.plan_list.select(answer.plan.or(Some(0)));
+        }
+        self.saved = saved;
+        if self.current_screen != CurrentScreen::Gate {
+            self.current_screen = self.resume_screen();
+        }
+        self
+    }
+
+    // The saved screen, if it still makes sense with this card and these
+    // answers. A card edited since last time can drop screens.
+    fn resume_screen(&self) -> CurrentScreen {
+        let Some(screen) = self.saved.screen.clone() else {
+            return CurrentScreen::IntroScreen;
+        };
+        let accepted = self.answer.as_ref().is_some_and(|answer| answer.accepted);
+        let chosen = self.answer.as_ref().is_some_and(|answer| answer.plan.is_some());
+        let fits = match screen {
+            CurrentScreen::Gate => false,
+            CurrentScreen::IntroScreen
+            | CurrentScreen::WillYouBeMyValentine
+            | CurrentScreen::NotValentine => true,
+            CurrentScreen::Advent => !self.card.advent_days().is_empty(),
+            CurrentScreen::YesValentine | CurrentScreen::Rsvp => accepted,
+            CurrentScreen::DateChoice => accepted && self.card.plans.len() > 1,
+            CurrentScreen::DateInfo | CurrentScreen::Celebration => chosen,
+            CurrentScreen::Itinerary => chosen && !self.card.itinerary.is_empty(),
+            CurrentScreen::Anniversary => chosen && self.card.anniversary.is_some(),
+            CurrentScreen::Letters => chosen && !self.card.letters.is_empty(),
+            CurrentScreen::Custom(_) => self.scenes.contains(&screen),
+        };
+        match screen {
+            CurrentScreen::Celebration if fits => CurrentScreen::DateInfo,
+            _ if fits => screen,
+            _ if chosen => CurrentScreen::DateInfo,
+            _ if accepted => CurrentScreen::YesValentine,
+            _ => CurrentScreen::IntroScreen,
+        }
+    }
+
+    // Every screen change goes through here so progress is saved as it happens.
+    pub fn go_to(&mut self, screen: CurrentScreen) {
+        if screen != self.current_screen {
+            self.remember();
+        }
+        self.dirty = true;
+        self.current_screen = screen;
+        self.save_state();
+    }
+
+    // Every screen is remembered, and whether Back can return to it is asked
+    // when it's needed: the scene may be busy handling the key that left it.
+    fn remember(&mut self) {
+        if self.history.len() == MAX_HISTORY {
+            self.history.remove(0);
+        }
+        self.history.push(Visit {
+            screen: self.current_screen.clone(),
+            intro_screen: self.intro_screen,
+        });
+    }
+
+    // Whether Backspace can return to `screen`, up to its scene.
+    fn revisitable(&self, screen: &CurrentScreen) -> bool {
+        self.scenes.get(screen).is_some_and(|scene| scene.revisitable(self))
+    }
+
+    // What the keys do on the current screen, for the help overlay.
+    pub fn help_entries(&self) -> Vec<(String, &'static str)> {
+        let keys = &self.card.keys;
+        let key = |action| keys.label(action);
+        let mut entries = if self.is_locked(&self.current_screen) {
+            Vec::new()
+        } else {
+            let scene = self.scenes.get(&self.current_screen);
+            scene.map(|scene| scene.help(self)).unwrap_or_default()
+        };
+        if self.can_go_back() {
+            entries.push((key(Action::Back), "go back"));
         }
+        entries.push((key(Action::Pause), "pause the animations"));
+        entries.push((key(Action::Help), "show or hide this help"));
+        entries.push((key(Action::Quit), "quit"));
+        entries
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        let here = Visit {
+            screen: self.current_screen.clone(),
+            intro_screen: self.intro_screen,
+        };
+        self.history
+            .iter()
+            .any(|visit| *visit != here && self.revisitable(&visit.screen))
+    }
+
+    // Returns to the last screen that can still be visited, as it was left.
+    // False when there's nowhere to go.
+    fn back(&mut self) -> bool {
+        let here = Visit {
+            screen: self.current_screen.clone(),
+            intro_screen: self.intro_screen,
+        };
+        while let Some(visit) = self.history.pop() {
+            if visit == here || !self.revisitable(&visit.screen) {
+                continue;
+            }
+            self.current_screen = visit.screen;
+            self.intro_screen = visit.intro_screen;
+            self.save_state();
+            return true;
+        }
+        false
+    }
+
+    // Any screen the card names that isn't one of the built in ones has to be
+    // a scene added with with_scene, or it's a typo that would never match.
+    pub fn check_screens(&self) -> io::Result<()> {
+        let named = self.card.locks.iter().map(|lock| &lock.screen);
+        for screen in named.chain(self.card.backgrounds.screens.keys()) {
+            if let CurrentScreen::Custom(SceneId(name)) = screen {
+                if !self.scenes.contains(screen) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("the card names a screen \"{}\" that doesn't exist", name),
+                    ));
+                }
+            }
+        }
+        Ok(())
     }
 
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
-        let tick_rate = Duration::from_millis(16);
+        self.check_screens()?;
         let mut last_tick = Instant::now();
         while !self.exit {
-            terminal.draw(|frame| ui(frame, self.borrow_mut()))?;
+            if self.dirty {
+                terminal.draw(|frame| {
+                    frame.render_stateful_widget(CardWidget, frame.area(), self);
+                    if let Some(cursor) = self.cursor {
+                        frame.set_cursor_position(cursor);
+                    }
+                })?;
+            }
 
-            if event::poll(Duration::from_millis(100))? {
-                if let Event::Key(key_event) = event::read()? {
-                    self.handle_key_event(key_event).unwrap();
-                }
+            // wait for input until the next tick is due, and no longer
+            if event::poll(TICK_RATE.saturating_sub(last_tick.elapsed()))? {
+                self.handle_event(&event::read()?);
             }
-            if last_tick.elapsed() >= tick_rate {
+            if last_tick.elapsed() >= TICK_RATE {
                 self.on_tick();
                 last_tick = Instant::now();
             }
         }
+        self.save_state();
         Ok(())
     }
 
-    fn on_tick(&mut self) {
+    pub fn on_tick(&mut self) {
+        // the clocks move whatever else is going on
+        if self.clock.now().timestamp() != self.drawn_second {
+            self.dirty = true;
+        }
+        if self.paused {
+            self.check_countdown();
+            self.settle();
+            return;
+        }
         self.tick_count += 1;
         
-        if (self.tick_count % 12) == 0 {
+        if self.tick_count % 12 == 0 {
             self.offset = (self.offset + 1) % 4;
             // println!("{}",self.offset);
         }
-        let ball = self.heart.clone();
-        // println!("x&y: {},{}", ball.x, ball.y);
-        let playground = self.playground;
-        if ball.x - ball.radius < f64::from(playground.left())
-            || ball.x + ball.radius > f64::from(playground.right())
-        {
-            self.vx = -self.vx;
+        self.check_countdown();
+        if let Some(transition) = &mut self.transition {
+            self.dirty = true;
+            if !transition.step() {
+                self.transition = None;
+            }
+        }
+        self.scene(self.current_screen.clone(), |scene, app| scene.on_tick(app));
+        self.settle();
+        if self.bounce.step(self.playground) {
+            self.bounce.burst();
         }
-        if ball.y - ball.radius < f64::from(playground.top())
-            || ball.y + ball.radius > f64::from(playground.bottom())
+        // a sealed screen is only an envelope
+        if !self.is_locked(&self.current_screen)
+            && self.scenes.get(&self.current_screen).is_some_and(|scene| scene.animated(self))
         {
-            self.vy = -self.vy;
+            self.dirty = true;
         }
-
-        self.heart.x += self.vx;
-        self.heart.y += self.vy;
     }
     fn exit(&mut self) {
         self.exit = true;
     }
+    pub fn reset_heart(&mut self) {
+        self.bounce.reset();
+    }
+    pub fn celebrate(&mut self) {
+        self.celebrate_until = self.tick_count + CELEBRATION_TICKS;
+    }
+
+    // Runs `f` with the scene for `screen`. It's out of the registry while
+    // `f` runs, so the scene and the app can both be borrowed mutably.
+    fn scene<R>(
+        &mut self,
+        screen: CurrentScreen,
+        f: impl FnOnce(&mut dyn Scene, &mut Self) -> R,
+    ) -> Option<R> {
+        let mut scene = self.scenes.take(&screen)?;
+        let result = f(scene.as_mut(), self);
+        self.scenes.put_back(screen, scene);
+        Some(result)
+    }
+
+    // Tells scenes they've been left or entered, after anything that can
+    // change the screen. Their hooks can change it again, hence the loop.
+    pub fn settle(&mut self) {
+        while self.entered.as_ref() != Some(&self.current_screen) {
+            let screen = self.current_screen.clone();
+            if let Some(left) = self.entered.replace(screen.clone()) {
+                self.scene(left, |scene, app| scene.on_exit(app));
+                self.start_transition();
+            }
+            self.scene(screen, |scene, app| scene.on_enter(app));
+        }
+    }
+
+    // Blends from the last frame into the new screen. Nothing moves while
+    // the animations are paused.
+    fn start_transition(&mut self) {
+        let style = &self.card.transitions;
+        self.transition = (!self.paused
+            && style.effect != Effect::None
+            && style.ticks > 0
+            && !self.last_frame.area.is_empty())
+        .then(|| Transition::new(style.effect, self.last_frame.clone(), style.ticks));
+    }
+
+    pub fn render_scene(&mut self, area: Rect, buf: &mut Buffer, main_chunks: Rc<[Rect]>) {
+        self.scene(self.current_screen.clone(), |scene, app| {
+            scene.render(area, buf, app, main_chunks)
+        });
+    }
+
+    // Fires once when the chosen plan's time goes from ahead of us to behind
+    // us while the card is open.
+    fn check_countdown(&mut self) {
+        let Some(plan) = self.chosen_plan() else {
+            return;
+        };
+        let pending = self.clock.now() < plan.local_time();
+        if self.countdown_pending && !pending {
+            let message = format!(
+                "It's time! Our date at {} starts now ({})",
+                plan.venue,
+                plan.time.format("%-I:%M %p")
+            );
+            // only for a date they said yes to and picked themselves, and
+            // only ever once, whatever they pick after or however often the
+            // card is reopened
+            let picked = self.answer.as_ref().is_some_and(|a| a.accepted && a.plan.is_some());
+            if picked && !self.saved.hooks_fired {
+                self.card.on_arrival.run(&message);
+                self.saved.hooks_fired = true;
+                self.save_state();
+            }
+            if let CurrentScreen::DateInfo | CurrentScreen::Itinerary = self.current_screen {
+                self.go_to(CurrentScreen::Celebration);
+            }
+        }
+        self.countdown_pending = pending;
+    }
+
+    // Progress is best effort, a read-only disk shouldn't stop the card.
+    pub fn save_state(&mut self) {
+        self.saved.screen = match &self.current_screen {
+            // the gate is asked every time, and a celebration only happens once
+            CurrentScreen::Gate => self.saved.screen.take(),
+            CurrentScreen::Celebration => Some(CurrentScreen::DateInfo),
+            screen => Some(screen.clone()),
+        };
+        self.saved.intro_screen = self.intro_screen;
+        self.saved.answer = self.answer.clone();
+        self.saved.history = self.history.clone();
+        if let Some(path) = &self.state_path {
+            let _ = self.saved.save(path);
+        }
+    }
+
+    pub fn is_locked(&self, screen: &CurrentScreen) -> bool {
+        self.card.locked_until(screen, self.clock.now()).is_some()
+    }
+
+    // Keeps the timeline on the event that is happening now, or the next one.
+    pub fn follow_itinerary(&mut self) {
+        let status = self.card.itinerary_status(self.clock.now());
+        let focus = status
+            .iter()
+            .position(|s| *s != EventStatus::Done)
+            .unwrap_or(status.len().saturating_sub(1));
+        self.itinerary_list.select(Some(focus));
+    }
+
+    // The plan the countdown runs against, falling back to the first one until
+    // the recipient has picked.
+    pub fn chosen_plan(&self) -> Option<&DatePlan> {
+        let index = self.answer.as_ref().and_then(|a| a.plan).unwrap_or(0);
+        self.card.plans.get(index).or(self.card.plans.first())
+    }
     pub fn pong_canvas(&self, area: Rect) -> impl Widget + '_ {
-        Canvas::default()
-            .background_color(Color::Black)
-            .block(Block::bordered().title("I love you!!!"))
-            .marker(self.marker)
-            .paint(|ctx| {
-                ctx.draw(&self.heart);
-            })
-            .x_bounds([0.0, f64::from(area.width)])
-            .y_bounds([0.0, f64::from(area.height)])
+        self.bounce.canvas(area)
     }
 }
 
-impl<'a> App<'a> {
-    fn handle_key_event(&mut self, key_event: KeyEvent) -> io::Result<()> {
+impl App {
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if key_event.kind == KeyEventKind::Release {
+            return;
+        }
+        let action = self.card.keys.action(key_event);
+        // any key skips a transition that's still playing, quitting still quits
+        if self.transition.take().is_some() && action != Some(Action::Quit) {
+            return;
+        }
+        let locked = self.is_locked(&self.current_screen);
+        let captured = !locked
+            && self
+                .scene(self.current_screen.clone(), |scene, app| {
+                    scene.captures(app, key_event, action)
+                })
+                .unwrap_or(false);
+        if !captured && self.handle_global_key(action) {
+            self.settle();
+            return;
+        }
+        // a sealed screen only shows its envelope until it opens
+        if !locked {
+            self.scene(self.current_screen.clone(), |scene, app| {
+                scene.handle_event(app, &Event::Key(key_event), action)
+            });
+        }
+        self.settle();
+    }
+    // Quit, help, pause and back work the same on every screen. True when
+    // the key was one of them.
+    fn handle_global_key(&mut self, action: Option<Action>) -> bool {
+        if self.show_help {
+            match action {
+                Some(Action::Quit) => self.exit(),
+                _ => self.show_help = false,
+            }
+            return true;
+        }
+        match action {
+            Some(Action::Quit) => self.exit(),
+            Some(Action::Help) => self.show_help = true,
+            Some(Action::Pause) => self.paused = !self.paused,
+            Some(Action::Back) if self.back() => {}
+            _ => return false,
+        }
+        true
+    }
+    // Everything the card is sent goes through here. Keys go past the
+    // keymap first, pastes and clicks straight to the scene.
+    pub fn handle_event(&mut self, event: &Event) {
+        // a moving pointer doesn't change anything, everything else might
+        if !matches!(event, Event::Mouse(MouseEvent { kind: MouseEventKind::Moved, .. })) {
+            self.dirty = true;
+        }
+        if let Event::Key(key_event) = event {
+            self.handle_key_event(*key_event);
+            return;
+        }
+        if let Event::Mouse(MouseEvent { kind: MouseEventKind::Down(_), .. }) = event {
+            if self.transition.take().is_some() {
+                return;
+            }
+        }
+        if self.is_locked(&self.current_screen) {
+            return;
+        }
+        self.scene(self.current_screen.clone(), |scene, app| scene.handle_event(app, event, None));
+        self.settle();
+    }
+    pub fn next_intro_screen(&mut self) {
+        let next = match self.intro_screen {
+            IntroScreenOptions::Screen1 => IntroScreenOptions::Screen2,
+            IntroScreenOptions::Screen2 => IntroScreenOptions::Screen3,
+            IntroScreenOptions::Screen3 => {
+                let next = if self.card.advent_days().is_empty() {
+                    CurrentScreen::WillYouBeMyValentine
+                } else {
+                    CurrentScreen::Advent
+                };
+                self.go_to(next);
+                return;
+            }
+        };
+        self.remember();
+        self.intro_screen = next;
+        self.save_state();
+    }
+    // The latest cell that can be opened today, so the calendar starts there.
+    pub fn advent_today(&self) -> usize {
+        let today = self.clock.now().date_naive();
+        self.card
+            .advent_days()
+            .iter()
+            .rposition(|day| *day <= today)
+            .unwrap_or(0)
+    }
+    pub fn handle_advent_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        let days = self.card.advent_days();
+        let last = days.len().saturating_sub(1);
+        if self.advent_open.is_some() && action.is_none() && key_event.code != KeyCode::Esc {
+            return;
+        }
+        if let Some(open) = self.advent_open.take() {
+            // the final cell leads into the question, back and esc only
+            // close it
+            if open == last && action == Some(Action::Advance) {
+                self.go_to(CurrentScreen::WillYouBeMyValentine);
+            }
+            return;
+        }
+        let cursor = self.advent_cursor;
         match key_event.code {
-            KeyCode::Char('q') => self.exit(),
-            _ => {
-                match self.current_screen {
-                    CurrentScreen::IntroScreen => {
-                        match self.intro_screen  {
-                            IntroScreenOptions::Screen1 => {
-                                self.intro_screen = IntroScreenOptions::Screen2;
-                            },
-                            IntroScreenOptions::Screen2 => {
-                                self.intro_screen = IntroScreenOptions::Screen3;
-                            },
-                            IntroScreenOptions::Screen3 => {
-                                self.intro_screen = IntroScreenOptions::Screen3;
-                                self.current_screen = CurrentScreen::WillYouBeMyValentine;
-                                self.heart.x = 10.0;
-                                self.heart.y = 10.0;
-                            }
-                        }
-                    },
-                    CurrentScreen::WillYouBeMyValentine => {
-                        self.handle_wybmv_event(key_event);
-                    },
-                    CurrentScreen::NotValentine => {
-                        self.handle_notvalentine_event(key_event);
-                                self.heart.x = 10.0;
-                                self.heart.y = 10.0;
-                    },
-                    CurrentScreen::YesValentine => {
-                        self.handle_yesvalentine_event(key_event);
-                                self.heart.x = 10.0;
-                                self.heart.y = 10.0;
-                    },
-                    CurrentScreen::DateInfo => {
-                    }
-
+            KeyCode::Left | KeyCode::Char('h') => self.advent_cursor = cursor.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => self.advent_cursor = (cursor + 1).min(last),
+            KeyCode::Up | KeyCode::Char('k') => self.advent_cursor = cursor.saturating_sub(7),
+            KeyCode::Down | KeyCode::Char('j') => self.advent_cursor = (cursor + 7).min(last),
+            _ if action == Some(Action::Advance) => {
+                let today = self.clock.now().date_naive();
+                let Some(day) = days.get(cursor).filter(|day| **day <= today) else {
+                    return;
+                };
+                if self.saved.opened_days.insert(*day) {
+                    self.save_state();
+                }
+                let has_surprise = self
+                    .card
+                    .advent
+                    .as_ref()
+                    .is_some_and(|advent| advent.surprise(cursor).is_some());
+                if cursor == last && !has_surprise {
+                    self.go_to(CurrentScreen::WillYouBeMyValentine);
+                } else {
+                    self.advent_open = Some(cursor);
                 }
             }
-                
+            _ => {}
         }
-        Ok(())
     }
-    fn handle_wybmv_event(&mut self, key_event : KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('y') | KeyCode::Char('Y') => {
-                self.current_screen = CurrentScreen::YesValentine
+    pub fn gate_attempts_left(&self) -> u32 {
+        self.card
+            .gate
+            .as_ref()
+            .map_or(0, |gate| gate.max_attempts.saturating_sub(self.saved.gate_attempts))
+    }
+    pub fn handle_gate_event(&mut self, key_event: KeyEvent) {
+        if key_event.code == KeyCode::Esc {
+            self.exit();
+            return;
+        }
+        let Some(gate) = &self.card.gate else {
+            self.go_to(self.resume_screen());
+            return;
+        };
+        if self.gate_attempts_left() == 0 || self.gate_input.handle_key(key_event) {
+            return;
+        }
+        if key_event.code != KeyCode::Enter || self.gate_input.value().trim().is_empty() {
+            return;
+        }
+        let passed = gate.check(self.gate_input.value());
+        self.gate_input.clear();
+        if passed {
+            self.saved.gate_attempts = 0;
+            self.go_to(self.resume_screen());
+        } else {
+            self.saved.gate_attempts += 1;
+            self.save_state();
+        }
+    }
+    pub fn handle_wybmv_event(&mut self, action: Option<Action>) {
+        match action {
+            Some(Action::Yes) => {
+                self.answer = Some(Answer {
+                    accepted: true,
+                    ..Answer::default()
+                });
+                self.go_to(CurrentScreen::YesValentine);
             },
-            KeyCode::Char('n') | KeyCode::Char('N') => {
-                self.current_screen = CurrentScreen::NotValentine
+            Some(Action::No) => {
+                self.saved.no_count += 1;
+                self.go_to(CurrentScreen::NotValentine)
             }
             _ => {}
                 
         }
 
     }
-    fn handle_notvalentine_event(&mut self, key_event : KeyEvent) {
+    pub fn handle_notvalentine_event(&mut self, action: Option<Action>) {
+        if action == Some(Action::Advance) {
+            self.go_to(CurrentScreen::WillYouBeMyValentine);
+        }
+    }
+    pub fn handle_yesvalentine_event(&mut self, action: Option<Action>) {
+        match action {
+            Some(Action::Yes) => {
+                self.go_to(CurrentScreen::Rsvp);
+            },
+            Some(Action::No) => {
+                self.go_to(CurrentScreen::WillYouBeMyValentine);
+            }
+            _ => {}
+
+        }
+    }
+    pub fn handle_rsvp_event(&mut self, key_event: KeyEvent) {
+        if self.rsvp_input.handle_key(key_event) {
+            return;
+        }
         match key_event.code {
-            _ => {
-                self.current_screen = CurrentScreen::WillYouBeMyValentine
+            KeyCode::Enter => {
+                let note = self.rsvp_input.value().trim().to_string();
+                self.answer.get_or_insert_with(Answer::default).note = note;
+                self.finish_rsvp();
+            }
+            KeyCode::Esc => {
+                self.rsvp_input.clear();
+                self.finish_rsvp();
             }
             _ => {}
-                
         }
-
     }
-    fn handle_yesvalentine_event(&mut self, key_event : KeyEvent) {
+    fn finish_rsvp(&mut self) {
+        if self.card.plans.len() > 1 {
+            self.go_to(CurrentScreen::DateChoice);
+        } else {
+            self.choose_plan(0);
+        }
+    }
+    pub fn handle_datechoice_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
         match key_event.code {
-            KeyCode::Char('y') | KeyCode::Char('Y') => {
-                self.current_screen = CurrentScreen::DateInfo
-            },
-            KeyCode::Char('n') | KeyCode::Char('N') => {
-                self.current_screen = CurrentScreen::WillYouBeMyValentine
+            KeyCode::Up | KeyCode::Char('k') => self.plan_list.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.plan_list.select_next(),
+            KeyCode::Home => self.plan_list.select_first(),
+            KeyCode::End => self.plan_list.select_last(),
+            _ if action == Some(Action::Advance) => {
+                if let Some(index) = self.plan_list.selected() {
+                    self.choose_plan(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    fn choose_plan(&mut self, index: usize) {
+        let index = index.min(self.card.plans.len().saturating_sub(1));
+        self.answer.get_or_insert_with(Answer::default).plan = Some(index);
+        self.go_to(CurrentScreen::DateInfo);
+    }
+    pub fn handle_dateinfo_event(&mut self, key_event: KeyEvent) {
+        match key_event.code {
+            KeyCode::Char('i') if !self.card.itinerary.is_empty() => {
+                self.go_to(CurrentScreen::Itinerary);
+            }
+            KeyCode::Char('a') if self.card.anniversary.is_some() => {
+                self.go_to(CurrentScreen::Anniversary);
+            }
+            KeyCode::Char('l') if !self.card.letters.is_empty() => {
+                self.go_to(CurrentScreen::Letters);
+            }
+            _ => {}
+        }
+    }
+    pub fn handle_letters_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => self.letter_list.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.letter_list.select_next(),
+            KeyCode::Char('l') | KeyCode::Esc => self.go_to(CurrentScreen::DateInfo),
+            // with nothing to go back to, back still leaves for the date
+            _ if action == Some(Action::Back) => self.go_to(CurrentScreen::DateInfo),
+            _ => {}
+        }
+    }
+    pub fn handle_anniversary_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        if let KeyCode::Char('a') | KeyCode::Esc = key_event.code {
+            self.go_to(CurrentScreen::DateInfo);
+        } else if action == Some(Action::Back) {
+            self.go_to(CurrentScreen::DateInfo);
+        }
+    }
+    pub fn handle_itinerary_event(&mut self, key_event: KeyEvent, action: Option<Action>) {
+        if let KeyCode::Char('i') | KeyCode::Esc = key_event.code {
+            self.go_to(CurrentScreen::DateInfo);
+        } else if action == Some(Action::Back) {
+            self.go_to(CurrentScreen::DateInfo);
+        }
+    }
+    pub fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        match mouse_event.kind {
+            MouseEventKind::ScrollUp => self.plan_list.select_previous(),
+            MouseEventKind::ScrollDown => self.plan_list.select_next(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let area = self.plan_list_area;
+                if !area.contains(Position::new(mouse_event.column, mouse_event.row)) {
+                    return;
+                }
+                // every plan is drawn as a two line item
+                let row = usize::from(mouse_event.row - area.y) / 2 + self.plan_list.offset();
+                if row >= self.card.plans.len() {
+                    return;
+                }
+                // clicking the highlighted plan picks it
+                if self.plan_list.selected() == Some(row) {
+                    self.choose_plan(row);
+                } else {
+                    self.plan_list.select(Some(row));
+                }
             }
             _ => {}
-
         }
     }
 }
@@ -0,0 +1,361 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    symbols::Marker,
+    widgets::{canvas::Canvas, Clear, Widget},
+};
+use serde::Deserialize;
+use std::{collections::HashMap, fmt};
+
+use crate::app::CurrentScreen;
+use crate::color::{mix, noise};
+use crate::shapes::{Figure, Motif, Transform};
+
+// Something animated to fill the space behind a screen's popups. Only the
+// cells inside `area` are drawn, and the same `tick` always draws the same
+// frame, so pausing the card freezes it.
+pub trait Background {
+    fn render(&self, area: Rect, buf: &mut Buffer, tick: u64);
+
+    // Ticks that draw the same picture map to the same frame, so a cached
+    // one can be reused until this changes.
+    fn frame(&self, tick: u64) -> u64 {
+        tick
+    }
+}
+
+// Which background each screen gets, from the card's [backgrounds] table.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Backgrounds {
+    // every screen not listed in `screens`
+    pub default: BackgroundKind,
+    pub screens: HashMap<CurrentScreen, BackgroundKind>,
+}
+
+impl Backgrounds {
+    pub fn for_screen(&self, screen: &CurrentScreen) -> BackgroundKind {
+        self.screens.get(screen).copied().unwrap_or(self.default)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundKind {
+    None,
+    #[default]
+    Marquee,
+    HeartRain,
+    Stars,
+    Bubbles,
+    Waves,
+    Shapes,
+}
+
+impl BackgroundKind {
+    pub fn background(self) -> Box<dyn Background> {
+        match self {
+            BackgroundKind::None => Box::new(Plain),
+            BackgroundKind::Marquee => Box::new(Marquee::default()),
+            BackgroundKind::HeartRain => Box::new(HeartRain::default()),
+            BackgroundKind::Stars => Box::new(Stars::default()),
+            BackgroundKind::Bubbles => Box::new(Bubbles::default()),
+            BackgroundKind::Waves => Box::new(Waves),
+            BackgroundKind::Shapes => Box::new(Drift::default()),
+        }
+    }
+}
+
+// The last background drawn, reused for as long as the same frame of the
+// same background is wanted in the same place.
+#[derive(Default)]
+pub struct BackgroundCache {
+    background: Option<(BackgroundKind, Box<dyn Background>)>,
+    frame: Option<(Rect, u64)>,
+    buffer: Buffer,
+}
+
+impl BackgroundCache {
+    pub fn render(&mut self, kind: BackgroundKind, area: Rect, buf: &mut Buffer, tick: u64) {
+        let background = match &mut self.background {
+            Some((cached, background)) if *cached == kind => background,
+            slot => {
+                self.frame = None;
+                &mut slot.insert((kind, kind.background())).1
+            }
+        };
+        let frame = Some((area, background.frame(tick)));
+        if self.frame != frame {
+            // resizing only allocates when the area grows
+            self.buffer.resize(area);
+            self.buffer.reset();
+            background.render(area, &mut self.buffer, tick);
+            self.frame = frame;
+        }
+        let area = area.intersection(buf.area);
+        let width = usize::from(area.width);
+        for y in area.top()..area.bottom() {
+            let from = self.buffer.index_of(area.x, y);
+            let to = buf.index_of(area.x, y);
+            buf.content[to..to + width].clone_from_slice(&self.buffer.content[from..from + width]);
+        }
+    }
+}
+
+impl fmt::Debug for BackgroundCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackgroundCache")
+            .field("background", &self.background.as_ref().map(|(kind, _)| kind))
+            .field("frame", &self.frame)
+            .finish()
+    }
+}
+
+// Blank and black, for the generators to draw over.
+fn clear(area: Rect, buf: &mut Buffer) {
+    Clear.render(area, buf);
+    buf.set_style(area, Style::new().bg(Color::Black));
+}
+
+// Draws `symbol` at column `x`, row `y` of `area` when that's inside it.
+fn put(area: Rect, buf: &mut Buffer, x: i32, y: i32, symbol: &str, style: Style) {
+    if x < 0 || y < 0 || x >= i32::from(area.width) || y >= i32::from(area.height) {
+        return;
+    }
+    let position = Position::new(area.x + x as u16, area.y + y as u16);
+    buf[position].set_symbol(symbol).set_style(style);
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Plain;
+
+impl Background for Plain {
+    fn render(&self, area: Rect, buf: &mut Buffer, _tick: u64) {
+        clear(area, buf);
+    }
+
+    fn frame(&self, _tick: u64) -> u64 {
+        0
+    }
+}
+
+// The words going by, every other row the other way.
+#[derive(Debug, Clone)]
+pub struct Marquee {
+    pub text: String,
+    // ticks per cell moved
+    pub speed: u64,
+}
+
+impl Default for Marquee {
+    fn default() -> Self {
+        Self {
+            text: "My Valentine My Lovely Lady My Love <3 ".into(),
+            speed: 3,
+        }
+    }
+}
+
+impl Background for Marquee {
+    fn render(&self, area: Rect, buf: &mut Buffer, tick: u64) {
+        clear(area, buf);
+        let chars: Vec<char> = self.text.chars().collect();
+        if chars.is_empty() {
+            return;
+        }
+        let style = Style::new()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::ITALIC | Modifier::BOLD);
+        let len = chars.len() as i64;
+        let moved = (tick / self.speed.max(1)) as i64;
+        for row in 0..area.height {
+            let shift = if row % 2 == 0 { moved } else { -moved } + i64::from(row) * 7;
+            for column in 0..area.width {
+                let c = chars[(i64::from(column) + shift).rem_euclid(len) as usize];
+                let position = Position::new(area.x + column, area.y + row);
+                buf[position].set_char(c).set_style(style);
+            }
+        }
+    }
+
+    fn frame(&self, tick: u64) -> u64 {
+        tick / self.speed.max(1)
+    }
+}
+
+// Hearts falling down some of the columns, trailing off behind them.
+#[derive(Debug, Clone)]
+pub struct HeartRain {
+    // the share of columns with hearts in them
+    pub density: f64,
+}
+
+impl Default for HeartRain {
+    fn default() -> Self {
+        Self { density: 0.35 }
+    }
+}
+
+impl Background for HeartRain {
+    fn render(&self, area: Rect, buf: &mut Buffer, tick: u64) {
+        clear(area, buf);
+        for column in 0..area.width {
+            if noise(column, 0) >= self.density {
+                continue;
+            }
+            let speed = 0.2 + noise(column, 1) * 0.6;
+            let length = 3 + (noise(column, 2) * 5.0) as i32;
+            let cycle = f64::from(i32::from(area.height) + length);
+            let head = ((tick as f64 * speed + noise(column, 3) * cycle) % cycle) as i32;
+            for behind in 0..length {
+                let (symbol, style) = match behind {
+                    0 => ("♥", Style::new().fg(Color::LightRed).bold()),
+                    _ if behind < length / 2 => ("♥", Style::new().fg(Color::Magenta)),
+                    _ => ("·", Style::new().fg(Color::DarkGray)),
+                };
+                put(area, buf, i32::from(column), head - behind, symbol, style);
+            }
+        }
+    }
+}
+
+// A sky of stars, each twinkling at its own pace, and the odd heart.
+#[derive(Debug, Clone)]
+pub struct Stars {
+    // the share of cells with a star
+    pub density: f64,
+}
+
+impl Default for Stars {
+    fn default() -> Self {
+        Self { density: 0.06 }
+    }
+}
+
+impl Background for Stars {
+    fn render(&self, area: Rect, buf: &mut Buffer, tick: u64) {
+        clear(area, buf);
+        for row in 0..area.height {
+            for column in 0..area.width {
+                let seed = noise(column, row);
+                if seed >= self.density {
+                    continue;
+                }
+                let shine = (tick as f64 * (0.05 + seed * 2.0) + seed * 100.0).sin();
+                let heart = seed < self.density / 12.0;
+                let (symbol, style) = if shine > 0.6 && heart {
+                    ("♥", Style::new().fg(Color::LightMagenta))
+                } else if shine > 0.6 {
+                    ("✦", Style::new().fg(Color::White).bold())
+                } else if shine > 0.0 {
+                    ("+", Style::new().fg(Color::Gray))
+                } else if shine > -0.5 {
+                    ("·", Style::new().fg(Color::DarkGray))
+                } else {
+                    continue;
+                };
+                put(area, buf, i32::from(column), i32::from(row), symbol, style);
+            }
+        }
+    }
+}
+
+// Bubbles drifting up from the bottom, wobbling a little on the way.
+#[derive(Debug, Clone)]
+pub struct Bubbles {
+    // cells of background per bubble
+    pub spacing: u32,
+}
+
+impl Default for Bubbles {
+    fn default() -> Self {
+        Self { spacing: 40 }
+    }
+}
+
+impl Background for Bubbles {
+    fn render(&self, area: Rect, buf: &mut Buffer, tick: u64) {
+        clear(area, buf);
+        let count = u32::from(area.width) * u32::from(area.height) / self.spacing.max(1);
+        let cycle = f64::from(area.height) + 2.0;
+        for bubble in 0..count.min(u32::from(u16::MAX)) as u16 {
+            let speed = 0.1 + noise(bubble, 1) * 0.3;
+            let risen = (tick as f64 * speed + noise(bubble, 2) * cycle) % cycle;
+            let wobble = (tick as f64 * 0.1 + f64::from(bubble)).sin() * 1.5;
+            let x = noise(bubble, 0) * f64::from(area.width) + wobble;
+            let y = f64::from(area.height) - risen;
+            let (symbol, style) = match noise(bubble, 3) {
+                size if size < 0.5 => ("°", Style::new().fg(Color::Gray)),
+                size if size < 0.85 => ("o", Style::new().fg(Color::LightMagenta)),
+                _ => ("O", Style::new().fg(Color::White)),
+            };
+            put(area, buf, x as i32, y.floor() as i32, symbol, style);
+        }
+    }
+}
+
+// Soft bands of colour rolling across, deep plum to pink.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Waves;
+
+impl Background for Waves {
+    fn render(&self, area: Rect, buf: &mut Buffer, tick: u64) {
+        clear(area, buf);
+        let t = tick as f64;
+        for row in 0..area.height {
+            for column in 0..area.width {
+                let (x, y) = (f64::from(column), f64::from(row));
+                let wave = (x * 0.25 + t * 0.15).sin() + (y * 0.5 + x * 0.1 - t * 0.1).sin();
+                let amount = (wave + 2.0) / 4.0;
+                let position = Position::new(area.x + column, area.y + row);
+                buf[position].set_bg(mix((40, 0, 40), (200, 40, 120), amount));
+            }
+        }
+    }
+}
+
+// Every motif, drifting across and slowly turning.
+#[derive(Debug, Clone)]
+pub struct Drift {
+    // cells of background per shape
+    pub spacing: u32,
+}
+
+impl Default for Drift {
+    fn default() -> Self {
+        Self { spacing: 150 }
+    }
+}
+
+impl Background for Drift {
+    fn render(&self, area: Rect, buf: &mut Buffer, tick: u64) {
+        clear(area, buf);
+        let colors = [Color::LightMagenta, Color::Magenta, Color::LightRed, Color::Red];
+        let count = u32::from(area.width) * u32::from(area.height) / self.spacing.max(1);
+        // two canvas units to a cell down, so the shapes keep their shape
+        let (width, height) = (f64::from(area.width), f64::from(area.height) * 2.0);
+        let t = tick as f64;
+        Canvas::default()
+            .background_color(Color::Black)
+            .marker(Marker::Braille)
+            .x_bounds([0.0, width])
+            .y_bounds([0.0, height])
+            .paint(|ctx| {
+                for shape in 0..count.max(1).min(u32::from(u16::MAX)) as u16 {
+                    let size = 1.5 + noise(shape, 4) * 2.0;
+                    let cycle = width + size * 4.0;
+                    let speed = 0.05 + noise(shape, 1) * 0.15;
+                    let x = (noise(shape, 0) * cycle + t * speed) % cycle - size * 2.0;
+                    let y = noise(shape, 2) * height;
+                    let turn = (noise(shape, 3) - 0.5) * 0.1;
+                    ctx.draw(&Figure {
+                        motif: Motif::ALL[usize::from(shape) % Motif::ALL.len()],
+                        transform: Transform::new(x, y, size).rotated(t * turn),
+                        color: colors[usize::from(shape) % colors.len()],
+                    });
+                }
+            })
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,313 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use serde::Deserialize;
+
+use crate::advent::Advent;
+use crate::anniversary::Anniversary;
+use crate::background::Backgrounds;
+use crate::app::CurrentScreen;
+use crate::gate::Gate;
+use crate::heart::{HeartStyle, Trail};
+use crate::hooks::ArrivalHooks;
+use crate::keymap::Keymap;
+use crate::seal::Sealed;
+use crate::shapes::Sprite;
+use crate::timezones::LongDistance;
+use crate::transition::Transitions;
+use crate::vault::{self, Locked};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+pub const DEFAULT_CARD_PATH: &str = "card.toml";
+
+// Everything personal on the card lives here so the screens only deal with
+// presentation. Any field missing from the card file keeps its default.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Card {
+    pub plans: Vec<DatePlan>,
+    pub itinerary: Vec<ItineraryEvent>,
+    pub on_arrival: ArrivalHooks,
+    pub anniversary: Option<Anniversary>,
+    pub long_distance: Option<LongDistance>,
+    pub locks: Vec<ScreenLock>,
+    pub letters: Vec<Letter>,
+    pub advent: Option<Advent>,
+    pub gate: Option<Gate>,
+    pub keys: Keymap,
+    pub transitions: Transitions,
+    pub backgrounds: Backgrounds,
+    // what bounces around under every screen
+    pub bouncing: Sprite,
+    pub heart: HeartStyle,
+    pub trail: Trail,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DatePlan {
+    pub venue: String,
+    #[serde(with = "wall_clock")]
+    pub time: NaiveDateTime,
+    #[serde(default)]
+    pub dress_code: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ItineraryEvent {
+    pub name: String,
+    #[serde(with = "wall_clock")]
+    pub time: NaiveDateTime,
+    #[serde(default, with = "wall_clock::option")]
+    pub ends: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub place: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+// Keeps a whole screen sealed until `unlock_at`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScreenLock {
+    pub screen: CurrentScreen,
+    #[serde(with = "wall_clock")]
+    pub unlock_at: NaiveDateTime,
+}
+
+// A message that can be read from the letters screen once `unlock_at` has
+// passed, or straight away without one.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Letter {
+    pub title: String,
+    #[serde(default, with = "wall_clock::option")]
+    pub unlock_at: Option<NaiveDateTime>,
+    pub body: Sealed,
+}
+
+impl Letter {
+    pub fn locked_until(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        self.unlock_at.map(|at| local_time(&at)).filter(|at| now < *at)
+    }
+}
+
+// Card times are written as wall clock times where the date happens.
+pub fn local_time(time: &NaiveDateTime) -> DateTime<Local> {
+    Local
+        .from_local_datetime(time)
+        .earliest()
+        .unwrap_or_else(|| time.and_utc().with_timezone(&Local))
+}
+
+impl DatePlan {
+    pub fn local_time(&self) -> DateTime<Local> {
+        local_time(&self.time)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventStatus {
+    Done,
+    Now,
+    Next,
+    Later,
+}
+
+impl ItineraryEvent {
+    pub fn local_time(&self) -> DateTime<Local> {
+        local_time(&self.time)
+    }
+}
+
+impl Card {
+    // When `screen` opens, if it is still sealed at `now`. With several locks
+    // on one screen the latest wins.
+    pub fn locked_until(&self, screen: &CurrentScreen, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        self.locks
+            .iter()
+            .filter(|lock| lock.screen == *screen)
+            .map(|lock| local_time(&lock.unlock_at))
+            .filter(|at| now < *at)
+            .max()
+    }
+
+    // The advent calendar's cells, ending on the first plan's day unless the
+    // calendar says otherwise.
+    pub fn advent_days(&self) -> Vec<NaiveDate> {
+        let Some(advent) = &self.advent else {
+            return Vec::new();
+        };
+        let fallback = self
+            .plans
+            .first()
+            .map_or(advent.start, |plan| plan.time.date());
+        advent.days(fallback)
+    }
+
+    // Where each itinerary event stands at `now`. An event without an end
+    // lasts until the next one starts, the last one gets two hours.
+    pub fn itinerary_status(&self, now: DateTime<Local>) -> Vec<EventStatus> {
+        let mut next_seen = false;
+        self.itinerary
+            .iter()
+            .enumerate()
+            .map(|(i, event)| {
+                let start = event.local_time();
+                let end = match (event.ends, self.itinerary.get(i + 1)) {
+                    (Some(ends), _) => local_time(&ends),
+                    (None, Some(next)) => next.local_time(),
+                    (None, None) => start + chrono::Duration::hours(2),
+                };
+                if now >= end {
+                    EventStatus::Done
+                } else if now >= start {
+                    EventStatus::Now
+                } else if !next_seen {
+                    next_seen = true;
+                    EventStatus::Next
+                } else {
+                    EventStatus::Later
+                }
+            })
+            .collect()
+    }
+
+    pub fn parse(text: &str, path: &Path) -> io::Result<Self> {
+        let mut card: Card = toml::from_str(text).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), err),
+            )
+        })?;
+        card.itinerary.sort_by_key(|event| event.time);
+        Ok(card)
+    }
+}
+
+// The card named on the command line, or card.toml when it exists, or the
+// built in card. An encrypted file stays locked until someone gives the
+// passphrase.
+pub enum CardFile {
+    Plain(Box<Card>),
+    Encrypted { path: PathBuf, locked: Locked },
+}
+
+impl CardFile {
+    pub fn read(path: Option<&Path>) -> io::Result<Self> {
+        let path = match path {
+            Some(path) => path,
+            None if Path::new(DEFAULT_CARD_PATH).exists() => Path::new(DEFAULT_CARD_PATH),
+            None => return Ok(CardFile::Plain(Box::default())),
+        };
+        let text = fs::read_to_string(path)?;
+        if vault::is_encrypted(&text) {
+            let locked = Locked::parse(&text).map_err(|err| {
+                io::Error::new(err.kind(), format!("{}: {}", path.display(), err))
+            })?;
+            return Ok(CardFile::Encrypted {
+                path: path.to_path_buf(),
+                locked,
+            });
+        }
+        Card::parse(&text, path).map(|card| CardFile::Plain(Box::new(card)))
+    }
+}
+
+impl Default for Card {
+    fn default() -> Self {
+        let date = NaiveDate::from_ymd_opt(2025, 2, 15).unwrap();
+        Self {
+            plans: vec![
+                DatePlan {
+                    venue: "J Wilson's".into(),
+                    time: date.and_hms_opt(16, 0, 0).unwrap(),
+                    dress_code: "Something sexy".into(),
+                    description: "Burgers, fries and way too many milkshakes.".into(),
+                },
+                DatePlan {
+                    venue: "The Botanical Gardens".into(),
+                    time: date.and_hms_opt(13, 0, 0).unwrap(),
+                    dress_code: "Comfy shoes".into(),
+                    description: "A walk through the greenhouse, then a picnic on the lawn."
+                        .into(),
+                },
+                DatePlan {
+                    venue: "Movie night at mine".into(),
+                    time: date.and_hms_opt(19, 30, 0).unwrap(),
+                    dress_code: "Pajamas".into(),
+                    description: "Blanket fort, homemade pizza and your pick of the movie."
+                        .into(),
+                },
+            ],
+            itinerary: Vec::new(),
+            on_arrival: ArrivalHooks::default(),
+            anniversary: None,
+            long_distance: None,
+            locks: Vec::new(),
+            letters: Vec::new(),
+            advent: None,
+            gate: None,
+            keys: Keymap::default(),
+            transitions: Transitions::default(),
+            backgrounds: Backgrounds::default(),
+            bouncing: Sprite::default(),
+            heart: HeartStyle::default(),
+            trail: Trail::default(),
+        }
+    }
+}
+
+// Accepts both TOML date-times (`time = 2025-02-15T16:00:00`) and the same
+// thing written as a string. Times with an offset are moved to local time, a
+// bare date means midnight.
+pub mod wall_clock {
+    use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+    use serde::{de::Error, Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<NaiveDateTime, D::Error> {
+        let datetime = toml::value::Datetime::deserialize(de)?;
+        let text = datetime.to_string();
+        let parsed = if datetime.offset.is_some() {
+            DateTime::parse_from_rfc3339(&text)
+                .map(|time| time.with_timezone(&Local).naive_local())
+        } else if datetime.time.is_some() {
+            NaiveDateTime::parse_from_str(&text, "%Y-%m-%dT%H:%M:%S%.f")
+        } else {
+            NaiveDate::parse_from_str(&text, "%Y-%m-%d").map(|date| date.and_time(Default::default()))
+        };
+        parsed.map_err(|_| D::Error::custom(format!("expected a date-time, got {text}")))
+    }
+
+    pub mod date {
+        use super::*;
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<NaiveDate, D::Error> {
+            super::deserialize(de).map(|time| time.date())
+        }
+    }
+
+    pub mod date_option {
+        use super::*;
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            de: D,
+        ) -> Result<Option<NaiveDate>, D::Error> {
+            super::deserialize(de).map(|time| Some(time.date()))
+        }
+    }
+
+    pub mod option {
+        use super::*;
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            de: D,
+        ) -> Result<Option<NaiveDateTime>, D::Error> {
+            super::deserialize(de).map(Some)
+        }
+    }
+}
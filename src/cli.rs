@@ -0,0 +1,107 @@
+use std::{env, ffi::OsString, io, path::PathBuf};
+
+pub const USAGE: &str = "\
+usage: valentines_rs [--reset] [CARD]
+                                     open the card (default: card.toml, else the built in card),
+                                     --reset forgets the saved progress and starts from the top
+                                     (wrong answers at the gate still count)
+       valentines_rs seal TEXT...    print TEXT sealed for use in a card file
+       valentines_rs gate-hash ANSWER...
+                                     print the salt and hash for a [gate] answer
+       valentines_rs encrypt CARD [OUT]
+                                     encrypt CARD with a passphrase (to OUT, else stdout)
+       valentines_rs decrypt CARD [OUT]
+                                     decrypt an encrypted CARD (to OUT, else stdout)";
+
+#[derive(Debug)]
+pub enum Command {
+    Run { card: Option<PathBuf>, reset: bool },
+    Seal { text: String },
+    GateHash { answer: String },
+    Encrypt { card: PathBuf, out: Option<PathBuf> },
+    Decrypt { card: PathBuf, out: Option<PathBuf> },
+    Help,
+}
+
+impl Command {
+    pub fn from_args() -> io::Result<Self> {
+        Self::parse(env::args_os().skip(1).collect())
+    }
+
+    fn parse(args: Vec<OsString>) -> io::Result<Self> {
+        let mut args = args.into_iter();
+        let Some(first) = args.next() else {
+            return parse_run(args);
+        };
+        match first.to_str() {
+            Some("-h" | "--help" | "help") => Ok(Command::Help),
+            Some("seal") => Ok(Command::Seal {
+                text: rest_as_text(args, "seal needs some text")?,
+            }),
+            Some("gate-hash") => Ok(Command::GateHash {
+                answer: rest_as_text(args, "gate-hash needs the answer")?,
+            }),
+            Some("encrypt") => {
+                let (card, out) = card_and_out(args, "encrypt")?;
+                Ok(Command::Encrypt { card, out })
+            }
+            Some("decrypt") => {
+                let (card, out) = card_and_out(args, "decrypt")?;
+                Ok(Command::Decrypt { card, out })
+            }
+            _ => parse_run(std::iter::once(first).chain(args)),
+        }
+    }
+}
+
+fn parse_run(args: impl Iterator<Item = OsString>) -> io::Result<Command> {
+    let mut card = None;
+    let mut reset = false;
+    for arg in args {
+        match arg.to_str() {
+            Some("--reset") => reset = true,
+            Some(flag) if flag.starts_with('-') => {
+                return Err(usage_error(&format!("unknown option {flag}")))
+            }
+            _ if card.is_some() => {
+                return Err(usage_error(&format!(
+                    "unexpected argument {}",
+                    arg.to_string_lossy()
+                )))
+            }
+            _ => card = Some(PathBuf::from(arg)),
+        }
+    }
+    Ok(Command::Run { card, reset })
+}
+
+fn rest_as_text(args: impl Iterator<Item = OsString>, missing: &str) -> io::Result<String> {
+    let words: Vec<String> = args
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    if words.is_empty() {
+        return Err(usage_error(missing));
+    }
+    Ok(words.join(" "))
+}
+
+fn card_and_out(
+    mut args: impl Iterator<Item = OsString>,
+    command: &str,
+) -> io::Result<(PathBuf, Option<PathBuf>)> {
+    let card = args
+        .next()
+        .ok_or_else(|| usage_error(&format!("{command} needs a card file")))?;
+    let out = args.next().map(PathBuf::from);
+    if let Some(extra) = args.next() {
+        return Err(usage_error(&format!(
+            "unexpected argument {}",
+            extra.to_string_lossy()
+        )));
+    }
+    Ok((card.into(), out))
+}
+
+fn usage_error(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, format!("{message}\n{USAGE}"))
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Duration, Local, NaiveDateTime};
+use std::{env, io};
+
+use crate::card::local_time;
+
+// Set to a local date-time (e.g. 2025-02-15T15:59:30) to preview the card as
+// if it were that moment. Only debug builds read it, so whoever the card is
+// sent to can't wind it forward and open everything early.
+pub const NOW_VAR: &str = "VALENTINE_NOW";
+
+// Source of "now" for everything time based on the card, so a preview can run
+// the card at a different moment than the wall clock.
+#[derive(Debug, Default, Clone, Copy)]
+pub enum Clock {
+    #[default]
+    System,
+    // wall clock shifted by a fixed amount, time still moves
+    Offset(Duration),
+}
+
+impl Clock {
+    // Runs the card as if it were `at`, starting now.
+    pub fn starting_at(at: DateTime<Local>) -> Self {
+        Clock::Offset(at - Local::now())
+    }
+
+    pub fn from_env() -> io::Result<Self> {
+        if !cfg!(debug_assertions) {
+            return Ok(Clock::System);
+        }
+        let Ok(value) = env::var(NOW_VAR) else {
+            return Ok(Clock::System);
+        };
+        NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M:%S")
+            .map(|at| Clock::starting_at(local_time(&at)))
+            .map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{NOW_VAR}={value}: {err}"),
+                )
+            })
+    }
+
+    pub fn now(&self) -> DateTime<Local> {
+        match self {
+            Clock::System => Local::now(),
+            Clock::Offset(offset) => Local::now() + *offset,
+        }
+    }
+}
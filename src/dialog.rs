@@ -0,0 +1,247 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Widget, Wrap},
+};
+
+// How big a dialog is along one axis. Whatever the policy, a dialog never
+// grows past the area it's drawn in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Size {
+    Fixed(u16),
+    Percentage(u16),
+    #[default]
+    FitContent,
+}
+
+// A bordered popup centred in the area it's rendered into: title, body,
+// a row of buttons and a hint along the bottom border. Screens with their
+// own content (inputs, lists) draw it into `inner` after rendering the frame.
+#[derive(Debug, Default, Clone)]
+pub struct Dialog<'a> {
+    title: Line<'a>,
+    hint: Option<Line<'a>>,
+    body: Text<'a>,
+    buttons: Vec<(String, Span<'a>)>,
+    style: Style,
+    border_style: Style,
+    title_style: Style,
+    width: Size,
+    height: Size,
+    padding: Padding,
+    alignment: Alignment,
+    middle: bool,
+}
+
+impl<'a> Dialog<'a> {
+    pub fn new(title: impl Into<Line<'a>>) -> Self {
+        Self {
+            title: title.into(),
+            padding: Padding::horizontal(1),
+            alignment: Alignment::Center,
+            ..Self::default()
+        }
+    }
+
+    pub fn body(mut self, body: impl Into<Text<'a>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    // Shown as "[key] label" along the bottom of the dialog.
+    pub fn button(mut self, key: impl Into<String>, label: impl Into<Span<'a>>) -> Self {
+        self.buttons.push((key.into(), label.into()));
+        self
+    }
+
+    pub fn hint(mut self, hint: impl Into<Line<'a>>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn border_style(mut self, style: Style) -> Self {
+        self.border_style = style;
+        self
+    }
+
+    pub fn title_style(mut self, style: Style) -> Self {
+        self.title_style = style;
+        self
+    }
+
+    pub fn size(mut self, width: Size, height: Size) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn padding(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    // Centres the body vertically when the dialog is taller than it.
+    pub fn middle(mut self) -> Self {
+        self.middle = true;
+        self
+    }
+
+    // Where the dialog goes inside `outer`.
+    pub fn area(&self, outer: Rect) -> Rect {
+        let (chrome_width, chrome_height) = self.chrome();
+        let width = match self.width {
+            Size::Fixed(width) => width,
+            Size::Percentage(percent) => percent_of(outer.width, percent),
+            Size::FitContent => self.content_width().saturating_add(chrome_width),
+        }
+        .min(outer.width);
+        let height = match self.height {
+            Size::Fixed(height) => height,
+            Size::Percentage(percent) => percent_of(outer.height, percent),
+            Size::FitContent => {
+                let body_width = width.saturating_sub(chrome_width).max(1);
+                self.body_height(body_width).saturating_add(chrome_height)
+            }
+        }
+        .min(outer.height);
+        Rect {
+            x: outer.x + (outer.width - width) / 2,
+            y: outer.y + (outer.height - height) / 2,
+            width,
+            height,
+        }
+    }
+
+    // The space left for the body once borders, padding and buttons are taken.
+    pub fn inner(&self, outer: Rect) -> Rect {
+        let area = self.area(outer);
+        let mut inner = self.block(area).inner(area);
+        inner.height = inner.height.saturating_sub(self.buttons_height(inner));
+        inner
+    }
+
+    // Buttons sit a blank row under the body, or right under it when the
+    // body needs that row.
+    fn buttons_height(&self, inner: Rect) -> u16 {
+        if self.buttons.is_empty() {
+            0
+        } else if self.body_height(inner.width.max(1)) + 2 <= inner.height {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn chrome(&self) -> (u16, u16) {
+        let buttons = if self.buttons.is_empty() { 0 } else { 2 };
+        (
+            2 + self.padding.left + self.padding.right,
+            2 + self.padding.top + self.padding.bottom + buttons,
+        )
+    }
+
+    fn content_width(&self) -> u16 {
+        let title = self.title.width() + 2;
+        let hint = self.hint.as_ref().map_or(0, |hint| hint.width() + 2);
+        let buttons = self.buttons_line().width();
+        let body = self.body.width();
+        u16::try_from(body.max(title).max(hint).max(buttons)).unwrap_or(u16::MAX)
+    }
+
+    // Rough wrapped height: every line takes as many rows as its width needs.
+    fn body_height(&self, width: u16) -> u16 {
+        let width = usize::from(width);
+        let rows: usize = self
+            .body
+            .lines
+            .iter()
+            .map(|line| line.width().div_ceil(width).max(1))
+            .sum();
+        u16::try_from(rows).unwrap_or(u16::MAX)
+    }
+
+    fn buttons_line(&self) -> Line<'a> {
+        let mut spans = Vec::new();
+        for (i, (key, label)) in self.buttons.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::raw("   "));
+            }
+            spans.push(format!("[{key}] ").bold());
+            spans.push(label.clone());
+        }
+        Line::from(spans)
+    }
+
+    // Padding is the first thing to go when the dialog is squeezed.
+    fn block(&self, area: Rect) -> Block<'a> {
+        let (chrome_width, chrome_height) = self.chrome();
+        let padding = if area.width <= chrome_width || area.height <= chrome_height {
+            Padding::ZERO
+        } else {
+            self.padding
+        };
+        let mut block = Block::new()
+            .title(self.title.clone())
+            .title_style(self.title_style)
+            .borders(Borders::ALL)
+            .border_style(self.border_style)
+            .padding(padding)
+            .style(self.style);
+        if let Some(hint) = &self.hint {
+            block = block.title_bottom(hint.clone().centered());
+        }
+        block
+    }
+}
+
+impl Widget for Dialog<'_> {
+    fn render(self, outer: Rect, buf: &mut Buffer) {
+        let area = self.area(outer);
+        // too small for even the borders, leave the screen behind it alone
+        if area.width < 3 || area.height < 3 {
+            return;
+        }
+        Clear.render(area, buf);
+        let inner = self.inner(outer);
+        self.block(area).render(area, buf);
+
+        let buttons_height = self.buttons_height(self.block(area).inner(area));
+        if buttons_height > 0 && inner.bottom() < area.bottom().saturating_sub(1) {
+            let row = Rect {
+                y: inner.bottom() + buttons_height - 1,
+                height: 1,
+                ..inner
+            };
+            Paragraph::new(self.buttons_line())
+                .alignment(Alignment::Center)
+                .render(row, buf);
+        }
+
+        let mut body_area = inner;
+        if self.middle {
+            let height = self.body_height(inner.width.max(1)).min(inner.height);
+            body_area.y += (inner.height - height) / 2;
+            body_area.height = height;
+        }
+        Paragraph::new(self.body)
+            .wrap(Wrap { trim: false })
+            .alignment(self.alignment)
+            .render(body_area, buf);
+    }
+}
+
+fn percent_of(length: u16, percent: u16) -> u16 {
+    (u32::from(length) * u32::from(percent.min(100)) / 100) as u16
+}
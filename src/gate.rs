@@ -0,0 +1,104 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use serde::{Deserialize, Deserializer};
+use std::io;
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use crate::vault::{LANES, MEMORY_KIB, PASSES};
+
+// Written in front of the hash, so one from before answers went through
+// Argon2id gets a clear error instead of never matching.
+const HASH_PREFIX: &str = "argon2id:";
+
+// A personal question asked before the card opens. Only a salted hash of the
+// answer is kept in the card; make one with `valentines_rs gate-hash ANSWER`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Gate {
+    pub question: String,
+    pub salt: String,
+    // hex, without the prefix it's written with
+    #[serde(deserialize_with = "argon2_hash")]
+    pub hash: String,
+    // shown one more after every wrong answer
+    #[serde(default)]
+    pub hints: Vec<String>,
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+impl Gate {
+    pub fn check(&self, answer: &str) -> bool {
+        let Ok(attempt) = hash_answer(&self.salt, answer) else {
+            return false;
+        };
+        let expected = self.hash.trim().to_ascii_lowercase();
+        // compare every byte so timing doesn't give away a matching prefix
+        attempt.len() == expected.len()
+            && attempt
+                .bytes()
+                .zip(expected.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+
+    pub fn hints_after(&self, wrong_answers: u32) -> &[String] {
+        let shown = (wrong_answers as usize).min(self.hints.len());
+        &self.hints[..shown]
+    }
+}
+
+// "  Café   du Monde! " and "cafe du monde" are the same answer: accents,
+// case, punctuation and extra spaces don't count.
+pub fn normalize(answer: &str) -> String {
+    let plain: String = answer
+        .nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    plain.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Argon2id at the same costs as an encrypted card, so a short answer can't
+// be guessed quickly from a copy of the card.
+pub fn hash_answer(salt: &str, answer: &str) -> io::Result<String> {
+    let params = Params::new(MEMORY_KIB, PASSES, LANES, Some(32)).map_err(gate_error)?;
+    let mut hash = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(normalize(answer).as_bytes(), salt.as_bytes(), &mut hash)
+        .map_err(gate_error)?;
+    Ok(to_hex(&hash))
+}
+
+// The hash as the card has it, as `gate-hash` prints it.
+pub fn written_hash(hash: &str) -> String {
+    format!("{HASH_PREFIX}{hash}")
+}
+
+fn argon2_hash<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    match text.trim().strip_prefix(HASH_PREFIX) {
+        Some(hash) => Ok(hash.to_string()),
+        None => Err(serde::de::Error::custom(
+            "this hash is from an older version, make a new one with `valentines_rs gate-hash`",
+        )),
+    }
+}
+
+fn gate_error(err: argon2::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("gate salt: {err}"))
+}
+
+pub fn new_salt() -> String {
+    let mut salt = [0u8; 16];
+    getrandom::getrandom(&mut salt).expect("no system random number generator");
+    to_hex(&salt)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,391 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    symbols::Marker,
+    text::Line,
+    widgets::{
+        canvas::{Canvas, Context, Painter, Shape},
+        Block, Widget,
+    },
+};
+use serde::Deserialize;
+use std::{collections::VecDeque, f64::consts::TAU, time::Duration};
+
+use crate::app::ticks;
+use crate::color::{mix, noise, rgb};
+use crate::heart3d::Heart3d;
+use crate::shapes::{trace, Figure, Motif, Sample, Sprite, Transform};
+use crate::svg::Drawing;
+
+#[derive(Debug, Default, Clone)]
+pub struct Heart {
+    pub x: f64,
+    pub y: f64,
+    pub radius: f64,
+    pub color: Color,
+    pub fill: Fill,
+}
+
+// Written in the card as "outline", "solid" or { gradient = "#78001e" }.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "FillText")]
+pub enum Fill {
+    // just the line around it
+    #[default]
+    Outline,
+    Solid,
+    // `color` at the top down to this at the point
+    Gradient(Color),
+}
+
+// A fill as the card has it, before the colour is read.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FillText {
+    Outline,
+    Solid,
+    Gradient(String),
+}
+
+impl TryFrom<FillText> for Fill {
+    type Error = String;
+
+    fn try_from(text: FillText) -> Result<Self, Self::Error> {
+        match text {
+            FillText::Outline => Ok(Fill::Outline),
+            FillText::Solid => Ok(Fill::Solid),
+            FillText::Gradient(to) => to
+                .parse()
+                .map(Fill::Gradient)
+                .map_err(|_| format!("{to:?} isn't a colour, try a name or \"#rrggbb\"")),
+        }
+    }
+}
+
+impl Heart {
+    // x = 16 sin³t, y = 13 cos t - 5 cos 2t - 2 cos 3t - cos 4t, scaled and
+    // moved to where the heart is.
+    fn point(&self, t: f64) -> (f64, f64) {
+        let heart_x = 16.0 * t.sin().powi(3);
+        let heart_y =
+            13.0 * t.cos() - 5.0 * (2.0 * t).cos() - 2.0 * (3.0 * t).cos() - (4.0 * t).cos();
+        (self.radius.mul_add(heart_x, self.x), self.radius.mul_add(heart_y, self.y))
+    }
+
+    fn outline(&self, painter: &Painter<'_, '_>) -> Vec<Sample> {
+        trace(painter, |t| self.point(t * TAU))
+    }
+
+    // Fills between each pair of places a row crosses the outline, in half
+    // dot steps so no dot inside is missed.
+    fn fill_inside(
+        &self,
+        painter: &mut Painter<'_, '_>,
+        outline: &[Sample],
+        top: f64,
+        bottom: f64,
+    ) {
+        let Some((dot_width, dot_height)) = dot_size(outline) else {
+            return;
+        };
+        let mut y = bottom + dot_height / 4.0;
+        while y < top {
+            let mut crossings: Vec<f64> = outline
+                .iter()
+                .zip(outline.iter().cycle().skip(1))
+                .filter(|(a, b)| (a.1 <= y) != (b.1 <= y))
+                .map(|(a, b)| a.0 + (y - a.1) * (b.0 - a.0) / (b.1 - a.1))
+                .collect();
+            crossings.sort_by(f64::total_cmp);
+            let color = self.color_at(y, top, bottom);
+            for span in crossings.chunks_exact(2) {
+                let mut x = span[0];
+                while x <= span[1] {
+                    if let Some((x, y)) = painter.get_point(x, y) {
+                        painter.paint(x, y, color);
+                    }
+                    x += dot_width / 2.0;
+                }
+            }
+            y += dot_height / 2.0;
+        }
+    }
+
+    fn color_at(&self, y: f64, top: f64, bottom: f64) -> Color {
+        match self.fill {
+            Fill::Gradient(to) if top > bottom => {
+                let amount = (top - y) / (top - bottom);
+                mix(rgb(self.color, (255, 0, 0)), rgb(to, (255, 0, 0)), amount)
+            }
+            _ => self.color,
+        }
+    }
+}
+
+// How far apart the dots are on the canvas, worked out from where the outline
+// landed, as the painter doesn't say. None when the heart is too little on
+// the canvas to tell.
+fn dot_size(outline: &[Sample]) -> Option<(f64, f64)> {
+    let on_canvas = || outline.iter().filter_map(|&(x, y, dot)| Some((x, y, dot?)));
+    let left = on_canvas().min_by(|a, b| a.0.total_cmp(&b.0))?;
+    let right = on_canvas().max_by(|a, b| a.0.total_cmp(&b.0))?;
+    let bottom = on_canvas().min_by(|a, b| a.1.total_cmp(&b.1))?;
+    let top = on_canvas().max_by(|a, b| a.1.total_cmp(&b.1))?;
+    let across = right.2 .0 - left.2 .0;
+    let down = bottom.2 .1 - top.2 .1;
+    let width = (across >= 2).then(|| (right.0 - left.0) / across as f64);
+    let height = (down >= 2).then(|| (top.1 - bottom.1) / down as f64);
+    match (width, height) {
+        (Some(width), Some(height)) => Some((width, height)),
+        (Some(size), None) | (None, Some(size)) => Some((size, size)),
+        (None, None) => None,
+    }
+}
+
+impl Shape for Heart {
+    fn draw(&self, painter: &mut Painter<'_, '_>) {
+        let outline = self.outline(painter);
+        let top = outline.iter().map(|s| s.1).fold(f64::MIN, f64::max);
+        let bottom = outline.iter().map(|s| s.1).fold(f64::MAX, f64::min);
+        if self.fill != Fill::Outline {
+            self.fill_inside(painter, &outline, top, bottom);
+        }
+        for &(_, y, dot) in &outline {
+            if let Some((x, dot_y)) = dot {
+                painter.paint(x, dot_y, self.color_at(y, top, bottom));
+            }
+        }
+    }
+}
+
+// How much bigger than its radius a beating heart is `tick` ticks in: a
+// strong lub, a softer dub just after, then a rest.
+pub fn heartbeat(tick: u64) -> f64 {
+    let phase = (tick % BEAT_TICKS) as f64 / BEAT_TICKS as f64;
+    let pulse = |at: f64| (-((phase - at) / 0.08).powi(2)).exp();
+    1.0 + 0.25 * pulse(0.1) + 0.12 * pulse(0.35)
+}
+
+// ticks from one beat to the next, a little under a second
+pub const BEAT_TICKS: u64 = ticks(Duration::from_millis(800));
+
+// How the bouncing heart is drawn, from the card's [heart] table.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HeartStyle {
+    // only the heart itself is filled in, other shapes stay outlines
+    pub fill: Fill,
+    pub beating: bool,
+}
+
+// The heart bouncing around the bottom of every screen. It moves in screen
+// cells, so the area it bounces in can move and resize under it.
+#[derive(Debug, Clone)]
+pub struct Bounce {
+    pub heart: Heart,
+    // drawn where the heart is, at its size and in its colour
+    pub sprite: Sprite,
+    pub vx: f64,
+    pub vy: f64,
+    pub marker: Marker,
+    // ticks it's been moving, for the spinning heart
+    pub turn: u64,
+    pub trail: Trail,
+    // pulses with heartbeat as it goes
+    pub beating: bool,
+    // where it's been, oldest first, as many as the trail is long
+    pub history: VecDeque<(f64, f64)>,
+    pub sparkles: Vec<Sparkle>,
+}
+
+// Fading copies of the bouncing heart where it's just been, and sparks when
+// it hits a wall, from the card's [trail] table. Both are off unless asked
+// for.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Trail {
+    // how many copies it leaves behind
+    pub length: usize,
+    pub sparkles: bool,
+}
+
+// A spark flying off the wall, in screen cells like the heart.
+#[derive(Debug, Clone, Copy)]
+pub struct Sparkle {
+    pub x: f64,
+    pub y: f64,
+    pub vx: f64,
+    pub vy: f64,
+    pub age: u8,
+    pub life: u8,
+}
+
+// sparks per bounce
+const BURST: u16 = 8;
+
+impl Default for Bounce {
+    fn default() -> Self {
+        Self {
+            heart: Heart {
+                x: 5.0,
+                y: 5.0,
+                radius: 0.5,
+                color: Color::LightMagenta,
+                fill: Fill::Outline,
+            },
+            sprite: Sprite::default(),
+            vx: 3.0,
+            vy: 3.0,
+            marker: Marker::Braille,
+            turn: 0,
+            trail: Trail::default(),
+            beating: false,
+            history: VecDeque::new(),
+            sparkles: Vec::new(),
+        }
+    }
+}
+
+impl Bounce {
+    pub fn reset(&mut self) {
+        self.heart.x = 10.0;
+        self.heart.y = 10.0;
+        self.history.clear();
+        self.sparkles.clear();
+    }
+
+    // One tick of movement inside `area`. True when it turned off a wall.
+    pub fn step(&mut self, area: Rect) -> bool {
+        let ball = &self.heart;
+        let heading = (self.vx, self.vy);
+        // always head back inside, so a heart left outside by the terminal
+        // shrinking comes back instead of shaking against the wall
+        if ball.x - ball.radius < f64::from(area.left()) {
+            self.vx = self.vx.abs();
+        } else if ball.x + ball.radius > f64::from(area.right()) {
+            self.vx = -self.vx.abs();
+        }
+        if ball.y - ball.radius < f64::from(area.top()) {
+            self.vy = self.vy.abs();
+        } else if ball.y + ball.radius > f64::from(area.bottom()) {
+            self.vy = -self.vy.abs();
+        }
+
+        self.history.push_back((self.heart.x, self.heart.y));
+        while self.history.len() > self.trail.length {
+            self.history.pop_front();
+        }
+        for sparkle in &mut self.sparkles {
+            sparkle.x += sparkle.vx;
+            sparkle.y += sparkle.vy;
+            // they slow down as they go
+            sparkle.vx *= 0.8;
+            sparkle.vy *= 0.8;
+            sparkle.age += 1;
+        }
+        self.sparkles.retain(|sparkle| sparkle.age < sparkle.life);
+
+        self.heart.x += self.vx;
+        self.heart.y += self.vy;
+        self.turn += 1;
+        heading != (self.vx, self.vy)
+    }
+
+    // Sparks thrown off the heart, mostly the way it's now heading, away
+    // from the wall it hit.
+    pub fn burst(&mut self) {
+        if !self.trail.sparkles {
+            return;
+        }
+        let heading = self.vy.atan2(self.vx);
+        let seed = self.turn as u16;
+        for i in 0..BURST {
+            let angle = heading + (noise(seed, i) - 0.5) * 2.5;
+            let speed = 0.8 + noise(seed, i + BURST) * 1.5;
+            self.sparkles.push(Sparkle {
+                x: self.heart.x,
+                y: self.heart.y,
+                vx: speed * angle.cos(),
+                vy: speed * angle.sin(),
+                age: 0,
+                life: 5 + (noise(seed, i + 2 * BURST) * 6.0) as u8,
+            });
+        }
+    }
+
+    // The sprite drawn as `heart` would be.
+    fn draw(&self, ctx: &mut Context<'_>, heart: &Heart) {
+        // a heart's radius is 1/17 of its height
+        let transform = Transform::new(heart.x, heart.y, heart.radius * 17.0);
+        let color = heart.color;
+        match &self.sprite {
+            Sprite::Motif(Motif::Heart) | Sprite::SpinningHeart => ctx.draw(heart),
+            &Sprite::Motif(motif) => ctx.draw(&Figure { motif, transform, color }),
+            Sprite::Drawing(path) => ctx.draw(&Drawing { path, transform, color }),
+        }
+    }
+
+    pub fn canvas(&self, area: Rect) -> Playground<'_> {
+        Playground { bounce: self, bounds: area }
+    }
+}
+
+// What the bouncing heart is drawn on, with `bounds` in screen cells.
+#[derive(Debug, Clone)]
+pub struct Playground<'a> {
+    bounce: &'a Bounce,
+    bounds: Rect,
+}
+
+impl Widget for Playground<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (bounce, bounds) = (self.bounce, self.bounds);
+        let block = Block::bordered().title("I love you!!!");
+        if bounce.sprite == Sprite::SpinningHeart {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            buf.set_style(inner, Style::new().bg(Color::Black));
+            Heart3d::default().render(inner, buf, bounce.turn);
+            return;
+        }
+        Canvas::default()
+            .background_color(Color::Black)
+            .block(block)
+            .marker(bounce.marker)
+            .paint(|ctx| {
+                let mut heart = bounce.heart.clone();
+                if bounce.beating {
+                    heart.radius *= heartbeat(bounce.turn);
+                }
+                let color = rgb(heart.color, (255, 255, 255));
+                let copies = bounce.history.len() as f64 + 1.0;
+                // older copies are smaller and darker
+                for (i, &(x, y)) in bounce.history.iter().enumerate() {
+                    let fade = (i as f64 + 1.0) / copies;
+                    bounce.draw(ctx, &Heart {
+                        x,
+                        y,
+                        radius: heart.radius * (0.4 + 0.6 * fade),
+                        color: mix((0, 0, 0), color, fade * 0.7),
+                        fill: heart.fill,
+                    });
+                }
+                bounce.draw(ctx, &heart);
+                for sparkle in &bounce.sparkles {
+                    let fade = f64::from(sparkle.age) / f64::from(sparkle.life);
+                    let symbol = match fade {
+                        fade if fade < 0.3 => "✦",
+                        fade if fade < 0.7 => "+",
+                        _ => "·",
+                    };
+                    let style = Style::new().fg(mix((255, 255, 200), color, fade));
+                    ctx.print(sparkle.x, sparkle.y, Line::styled(symbol, style));
+                }
+            })
+            // the heart moves in screen cells, wherever the playground is
+            .x_bounds([f64::from(bounds.left()), f64::from(bounds.right())])
+            .y_bounds([f64::from(bounds.top()), f64::from(bounds.bottom())])
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,125 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+};
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use crate::color::mix;
+
+// Dimmest to brightest, as in the spinning donut.
+const RAMP: &[u8] = b".,-~:;=!*#$@";
+// How far the eye is from the middle of the heart, in heart sizes.
+const DISTANCE: f64 = 4.0;
+
+// A puffy heart turning on two axes, drawn in characters. Each cell shows
+// whichever bit of the surface is nearest, brighter the more it faces the
+// light.
+#[derive(Debug, Clone)]
+pub struct Heart3d {
+    // how far the middle puffs out each way, next to a height of 2
+    pub depth: f64,
+    // towards the light: up, left and in front
+    pub light: (f64, f64, f64),
+}
+
+impl Default for Heart3d {
+    fn default() -> Self {
+        Self {
+            depth: 0.4,
+            light: normalize((-0.5, 0.8, -0.7)),
+        }
+    }
+}
+
+impl Heart3d {
+    pub fn render(&self, area: Rect, buf: &mut Buffer, tick: u64) {
+        if area.is_empty() {
+            return;
+        }
+        let (width, height) = (usize::from(area.width), usize::from(area.height));
+        // nearness (1 / distance) of what's been drawn in each cell, and how bright
+        let mut nearest = vec![0.0; width * height];
+        let mut light = vec![0.0; width * height];
+        // rows per heart unit; cells are about twice as tall as wide
+        let scale = (height as f64 / 2.0).min(width as f64 / 4.0) * 0.9;
+        let t = tick as f64;
+        // both keep turning, at rates that don't line up, so it goes through
+        // every angle instead of repeating one wobble
+        let spin = (t * 0.05).sin_cos();
+        let tilt = (t * 0.031).sin_cos();
+        // enough points to land about two to a cell, for however big the
+        // heart comes out: it's 4 * scale columns across and its outline about
+        // 14 * scale long
+        let (around, across) = ((scale * 28.0).ceil() as usize, (scale * 7.0).ceil() as usize);
+        for i in 0..around {
+            let u = i as f64 * TAU / around as f64;
+            let (x, y, dx, dy) = outline(u);
+            for j in 0..=across {
+                let v = j as f64 * FRAC_PI_2 / across as f64;
+                let (sin_v, cos_v) = v.sin_cos();
+                for side in [1.0, -1.0] {
+                    // nested hearts pushed out front and back, and which way
+                    // the surface faces there
+                    let point = (x * sin_v, y * sin_v + 0.15, side * self.depth * cos_v);
+                    let normal = normalize((
+                        -self.depth * sin_v * dy,
+                        self.depth * sin_v * dx,
+                        side * cos_v * (dx * y - dy * x).abs(),
+                    ));
+                    let (px, py, pz) = turn(point, tilt, spin);
+                    let near = 1.0 / (DISTANCE + pz);
+                    let column = width as f64 / 2.0 + px * scale * 2.0 * DISTANCE * near;
+                    let row = height as f64 / 2.0 - py * scale * DISTANCE * near;
+                    if column < 0.0 || row < 0.0 || column >= width as f64 || row >= height as f64 {
+                        continue;
+                    }
+                    let cell = row as usize * width + column as usize;
+                    if near > nearest[cell] {
+                        nearest[cell] = near;
+                        let (nx, ny, nz) = turn(normal, tilt, spin);
+                        light[cell] = nx * self.light.0 + ny * self.light.1 + nz * self.light.2;
+                    }
+                }
+            }
+        }
+        for (cell, &near) in nearest.iter().enumerate() {
+            let position = Position::new(
+                area.x + (cell % width) as u16,
+                area.y + (cell / width) as u16,
+            );
+            if near == 0.0 {
+                continue;
+            }
+            let lit = light[cell].clamp(0.0, 1.0);
+            let symbol = RAMP[(lit * (RAMP.len() - 1) as f64).round() as usize];
+            buf[position]
+                .set_char(char::from(symbol))
+                .set_fg(mix((90, 0, 25), (255, 170, 200), lit));
+        }
+    }
+}
+
+// The flat heart a unit high, and which way it's heading at `u`.
+fn outline(u: f64) -> (f64, f64, f64, f64) {
+    let (sin, cos) = u.sin_cos();
+    let x = 16.0 * sin.powi(3);
+    let y = 13.0 * cos - 5.0 * (2.0 * u).cos() - 2.0 * (3.0 * u).cos() - (4.0 * u).cos();
+    let dx = 48.0 * sin * sin * cos;
+    let dy = -13.0 * sin + 10.0 * (2.0 * u).sin() + 6.0 * (3.0 * u).sin() + 4.0 * (4.0 * u).sin();
+    (x / 17.0, y / 17.0, dx / 17.0, dy / 17.0)
+}
+
+// Tips a point forward about x, then spins it about y, each by the sine and
+// cosine of its angle.
+fn turn((x, y, z): (f64, f64, f64), tilt: (f64, f64), spin: (f64, f64)) -> (f64, f64, f64) {
+    let (y, z) = (y * tilt.1 - z * tilt.0, y * tilt.0 + z * tilt.1);
+    (x * spin.1 + z * spin.0, y, z * spin.1 - x * spin.0)
+}
+
+fn normalize((x, y, z): (f64, f64, f64)) -> (f64, f64, f64) {
+    let length = (x * x + y * y + z * z).sqrt();
+    if length == 0.0 {
+        return (0.0, 0.0, -1.0);
+    }
+    (x / length, y / length, z / length)
+}
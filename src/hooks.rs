@@ -0,0 +1,44 @@
+use serde::Deserialize;
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+// Things to do the moment the countdown reaches zero. Each one runs once and
+// failures are ignored, the card keeps going either way.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ArrivalHooks {
+    // ring the terminal bell
+    pub bell: bool,
+    // POST the message to this url
+    pub webhook: Option<String>,
+    // append the message to this file
+    pub file: Option<PathBuf>,
+}
+
+impl ArrivalHooks {
+    pub fn run(&self, message: &str) {
+        if self.bell {
+            let mut stdout = io::stdout();
+            let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+        }
+        if let Some(path) = &self.file {
+            let _ = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{message}"));
+        }
+        if let Some(url) = &self.webhook {
+            // the request must not hold up drawing, so it goes on the runtime
+            if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                let request = reqwest::Client::new().post(url).body(message.to_string());
+                runtime.spawn(async move {
+                    let _ = request.send().await;
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,452 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Paragraph, StatefulWidget, Widget},
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+use zeroize::Zeroize;
+
+// Single line text input. The cursor is a byte offset into `value` that always
+// sits on a grapheme boundary, so accents and emoji are moved over as one unit.
+#[derive(Debug, Default, Clone)]
+pub struct TextInput {
+    value: String,
+    cursor: usize,
+    max_len: Option<usize>,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.cursor = 0;
+    }
+
+    // Like clear, but overwrites the old text first, for secrets.
+    pub fn wipe(&mut self) {
+        self.value.zeroize();
+        self.cursor = 0;
+    }
+
+    // Whether `key` is one of the ctrl or alt editing keys handle_key knows.
+    // Anything else with those held isn't typing.
+    pub fn shortcut(key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char('w' | 'u' | 'a' | 'e') => ctrl,
+            KeyCode::Backspace | KeyCode::Left | KeyCode::Right => ctrl || alt,
+            _ => false,
+        }
+    }
+
+    // Returns true when the key was used by the input, so callers can fall
+    // through to their own bindings (enter, esc, ...) otherwise.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.kind == KeyEventKind::Release {
+            return false;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char('w') if ctrl => self.delete_word_back(),
+            KeyCode::Char('u') if ctrl => {
+                self.value.drain(..self.cursor);
+                self.cursor = 0;
+            }
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = self.value.len(),
+            KeyCode::Char(_) if ctrl || alt => return false,
+            KeyCode::Char(c) => self.insert_char(c),
+            KeyCode::Backspace if ctrl || alt => self.delete_word_back(),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left if ctrl || alt => self.cursor = self.prev_word(),
+            KeyCode::Right if ctrl || alt => self.cursor = self.next_word(),
+            KeyCode::Left => self.cursor = self.prev_grapheme(),
+            KeyCode::Right => self.cursor = self.next_grapheme(),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.value.len(),
+            _ => return false,
+        }
+        true
+    }
+
+    // Pasted text is flattened onto one line.
+    pub fn insert_str(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '\r' => {}
+                '\n' | '\t' => self.insert_char(' '),
+                c if c.is_control() => {}
+                c => self.insert_char(c),
+            }
+        }
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        if self
+            .max_len
+            .is_some_and(|max| self.value.chars().count() >= max)
+        {
+            return;
+        }
+        self.value.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+        // a combining mark joins the previous grapheme, keep the cursor after it
+        self.cursor = self.next_boundary_from(self.cursor);
+    }
+
+    pub fn backspace(&mut self) {
+        let start = self.prev_grapheme();
+        self.value.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    pub fn delete(&mut self) {
+        let end = self.next_grapheme();
+        self.value.drain(self.cursor..end);
+    }
+
+    pub fn delete_word_back(&mut self) {
+        let start = self.prev_word();
+        self.value.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    fn prev_grapheme(&self) -> usize {
+        self.value[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_grapheme(&self) -> usize {
+        self.value[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(self.cursor, |g| self.cursor + g.len())
+    }
+
+    fn next_boundary_from(&self, pos: usize) -> usize {
+        let mut end = 0;
+        for (i, g) in self.value.grapheme_indices(true) {
+            end = i + g.len();
+            if end >= pos {
+                break;
+            }
+        }
+        end.max(pos)
+    }
+
+    fn prev_word(&self) -> usize {
+        let mut start = 0;
+        let mut seen_word = false;
+        for (i, word) in self.value[..self.cursor].split_word_bound_indices().rev() {
+            let blank = word.trim().is_empty();
+            if seen_word && blank {
+                break;
+            }
+            seen_word |= !blank;
+            start = i;
+        }
+        start
+    }
+
+    fn next_word(&self) -> usize {
+        let mut end = self.cursor;
+        let mut seen_word = false;
+        for word in self.value[self.cursor..].split_word_bounds() {
+            let blank = word.trim().is_empty();
+            if seen_word && blank {
+                break;
+            }
+            seen_word |= !blank;
+            end += word.len();
+        }
+        end
+    }
+
+    // Display width of the text before the cursor, wide characters count twice.
+    fn cursor_width(&self) -> usize {
+        self.value[..self.cursor].width()
+    }
+
+    fn graphemes_before_cursor(&self) -> usize {
+        self.value[..self.cursor].graphemes(true).count()
+    }
+}
+
+// Renders a `TextInput` into a single line, scrolling horizontally so the
+// cursor stays visible.
+#[derive(Debug, Clone)]
+pub struct TextInputWidget<'a> {
+    input: &'a TextInput,
+    block: Option<Block<'a>>,
+    style: Style,
+    placeholder: Option<Span<'a>>,
+    mask: Option<char>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TextInputState {
+    scroll: usize,
+    pub cursor: Option<Position>,
+}
+
+impl<'a> TextInputWidget<'a> {
+    pub fn new(input: &'a TextInput) -> Self {
+        Self {
+            input,
+            block: None,
+            style: Style::default(),
+            placeholder: None,
+            mask: None,
+        }
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn placeholder(mut self, placeholder: impl Into<Span<'a>>) -> Self {
+        self.placeholder = Some(placeholder.into());
+        self
+    }
+
+    // Shows one `mask` per character instead of the text, for passphrases.
+    pub fn mask(mut self, mask: char) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+}
+
+impl StatefulWidget for TextInputWidget<'_> {
+    type State = TextInputState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let input = self.input;
+        let inner = match &self.block {
+            Some(block) => block.inner(area),
+            None => area,
+        };
+        if let Some(block) = self.block {
+            block.render(area, buf);
+        }
+        if inner.width == 0 || inner.height == 0 {
+            state.cursor = None;
+            return;
+        }
+
+        let width = usize::from(inner.width) - 1;
+        let cursor = match self.mask {
+            Some(_) => input.graphemes_before_cursor(),
+            None => input.cursor_width(),
+        };
+        if cursor < state.scroll {
+            state.scroll = cursor;
+        } else if cursor > state.scroll + width {
+            state.scroll = cursor - width;
+        }
+
+        let line = if input.is_empty() {
+            Line::from(self.placeholder.unwrap_or_default())
+        } else if let Some(mask) = self.mask {
+            let shown = input.value.graphemes(true).count() - state.scroll;
+            Line::from(mask.to_string().repeat(shown))
+        } else {
+            Line::from(skip_columns(&input.value, state.scroll))
+        };
+        Paragraph::new(line).style(self.style).render(inner, buf);
+
+        let x = inner.x + u16::try_from(cursor - state.scroll).unwrap_or(inner.width - 1);
+        state.cursor = Some(Position::new(x.min(inner.right() - 1), inner.y));
+    }
+}
+
+// Drops `columns` display cells from the front of `text` without splitting a
+// grapheme; a wide character cut in half is replaced by a space.
+fn skip_columns(text: &str, columns: usize) -> String {
+    let mut skipped = 0;
+    let mut out = String::new();
+    for g in text.graphemes(true) {
+        if skipped >= columns {
+            out.push_str(g);
+            continue;
+        }
+        skipped += g.width();
+        if skipped > columns {
+            out.push(' ');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAMILY: &str = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+
+    fn press(input: &mut TextInput, code: KeyCode) {
+        input.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn ctrl(input: &mut TextInput, c: char) {
+        input.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL));
+    }
+
+    // a key at a time, as a terminal sends it
+    fn typed(text: &str) -> TextInput {
+        let mut input = TextInput::new();
+        for c in text.chars() {
+            press(&mut input, KeyCode::Char(c));
+        }
+        input
+    }
+
+    fn render(input: &TextInput, width: u16, state: &mut TextInputState) -> Buffer {
+        let area = Rect::new(0, 0, width, 1);
+        let mut buf = Buffer::empty(area);
+        TextInputWidget::new(input).render(area, &mut buf, state);
+        buf
+    }
+
+    #[test]
+    fn combining_marks_stay_with_their_letter() {
+        let mut input = typed("cafe\u{301}");
+        assert_eq!(input.value(), "cafe\u{301}");
+        assert_eq!(input.cursor, input.value().len());
+        press(&mut input, KeyCode::Left);
+        assert_eq!(input.cursor, 3);
+        press(&mut input, KeyCode::Right);
+        press(&mut input, KeyCode::Backspace);
+        assert_eq!(input.value(), "caf");
+
+        // a mark typed mid-word lands after the letter it joins
+        let mut input = typed("ab");
+        press(&mut input, KeyCode::Left);
+        press(&mut input, KeyCode::Char('\u{308}'));
+        assert_eq!(input.value(), "a\u{308}b");
+        assert_eq!(input.cursor, "a\u{308}".len());
+    }
+
+    #[test]
+    fn zwj_sequences_are_one_character() {
+        let mut input = typed(&format!("hi {FAMILY}"));
+        assert_eq!(input.cursor, input.value().len());
+        press(&mut input, KeyCode::Left);
+        assert_eq!(input.cursor, 3);
+        press(&mut input, KeyCode::Delete);
+        assert_eq!(input.value(), "hi ");
+
+        let mut input = typed(&format!("{FAMILY}{FAMILY}"));
+        press(&mut input, KeyCode::Backspace);
+        assert_eq!(input.value(), FAMILY);
+        assert_eq!(input.cursor, FAMILY.len());
+    }
+
+    #[test]
+    fn ctrl_w_takes_whole_words() {
+        let mut input = typed(&format!("hi {FAMILY}"));
+        ctrl(&mut input, 'w');
+        assert_eq!(input.value(), "hi ");
+
+        let mut input = typed("你好 世界  ");
+        ctrl(&mut input, 'w');
+        assert_eq!(input.value(), "你好 ");
+        ctrl(&mut input, 'w');
+        assert_eq!(input.value(), "");
+
+        let mut input = typed("and cafe\u{301}");
+        ctrl(&mut input, 'w');
+        assert_eq!(input.value(), "and ");
+    }
+
+    #[test]
+    fn wide_characters_count_two_columns() {
+        let mut input = typed("你好");
+        assert_eq!(input.cursor_width(), 4);
+        press(&mut input, KeyCode::Left);
+        assert_eq!(input.cursor, "你".len());
+        assert_eq!(input.cursor_width(), 2);
+        press(&mut input, KeyCode::Backspace);
+        assert_eq!(input.value(), "好");
+    }
+
+    #[test]
+    fn scrolls_to_keep_the_cursor_in_view() {
+        let mut state = TextInputState::default();
+        let mut input = typed("abcdefgh");
+        let buf = render(&input, 6, &mut state);
+        // one cell is kept free for the cursor at the end
+        assert_eq!(buf, Buffer::with_lines(["defgh "]));
+        assert_eq!(state.cursor, Some(Position::new(5, 0)));
+
+        press(&mut input, KeyCode::Home);
+        let buf = render(&input, 6, &mut state);
+        assert_eq!(buf, Buffer::with_lines(["abcdef"]));
+        assert_eq!(state.cursor, Some(Position::new(0, 0)));
+    }
+
+    #[test]
+    fn scrolling_never_splits_a_wide_character() {
+        let mut state = TextInputState::default();
+        let mut input = typed("你好世界");
+        let buf = render(&input, 6, &mut state);
+        // three columns scrolled off cuts 好 in half, so a space stands in
+        assert_eq!(buf, Buffer::with_lines([" 世界 "]));
+        assert_eq!(state.cursor, Some(Position::new(5, 0)));
+
+        press(&mut input, KeyCode::Left);
+        press(&mut input, KeyCode::Left);
+        press(&mut input, KeyCode::Left);
+        let buf = render(&input, 6, &mut state);
+        assert_eq!(buf, Buffer::with_lines(["好世界"]));
+        assert_eq!(state.cursor, Some(Position::new(0, 0)));
+    }
+
+    #[test]
+    fn masked_input_counts_characters() {
+        let input = typed(&format!("{FAMILY}e\u{301}你"));
+        let area = Rect::new(0, 0, 6, 1);
+        let mut buf = Buffer::empty(area);
+        let mut state = TextInputState::default();
+        TextInputWidget::new(&input).mask('*').render(area, &mut buf, &mut state);
+        assert_eq!(buf, Buffer::with_lines(["***   "]));
+        assert_eq!(state.cursor, Some(Position::new(3, 0)));
+    }
+
+    #[test]
+    fn max_len_counts_characters() {
+        let mut input = TextInput::new().with_max_len(3);
+        input.insert_str("你好世界");
+        assert_eq!(input.value(), "你好世");
+    }
+}
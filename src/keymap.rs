@@ -0,0 +1,196 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use serde::Deserialize;
+use std::{fmt, str::FromStr};
+
+// What a key press means, independent of which key it was. Screens ask for
+// actions so the keys can be changed from the card's [keys] table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Advance,
+    Yes,
+    No,
+    Back,
+    Quit,
+    Help,
+    Pause,
+}
+
+// Keys for each action. Actions left out of the card keep these defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Keymap {
+    pub advance: Vec<KeyBinding>,
+    pub yes: Vec<KeyBinding>,
+    pub no: Vec<KeyBinding>,
+    pub back: Vec<KeyBinding>,
+    pub quit: Vec<KeyBinding>,
+    pub help: Vec<KeyBinding>,
+    pub pause: Vec<KeyBinding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let keys = |keys: &[&str]| keys.iter().map(|key| key.parse().unwrap()).collect();
+        Self {
+            advance: keys(&["enter", "space"]),
+            yes: keys(&["y"]),
+            no: keys(&["n"]),
+            back: keys(&["backspace", "left"]),
+            quit: keys(&["q", "ctrl+c"]),
+            help: keys(&["?"]),
+            pause: keys(&["p"]),
+        }
+    }
+}
+
+impl Keymap {
+    pub fn bindings(&self, action: Action) -> &[KeyBinding] {
+        match action {
+            Action::Advance => &self.advance,
+            Action::Yes => &self.yes,
+            Action::No => &self.no,
+            Action::Back => &self.back,
+            Action::Quit => &self.quit,
+            Action::Help => &self.help,
+            Action::Pause => &self.pause,
+        }
+    }
+
+    // The first action bound to `key`. Releases never count.
+    pub fn action(&self, key: KeyEvent) -> Option<Action> {
+        if key.kind == KeyEventKind::Release {
+            return None;
+        }
+        [
+            Action::Quit,
+            Action::Help,
+            Action::Back,
+            Action::Yes,
+            Action::No,
+            Action::Pause,
+            Action::Advance,
+        ]
+        .into_iter()
+        .find(|action| self.bindings(*action).iter().any(|binding| binding.matches(key)))
+    }
+
+    // The first key for `action`, for hints like "press enter to continue".
+    pub fn hint(&self, action: Action) -> String {
+        self.bindings(action)
+            .first()
+            .map_or_else(|| "?".into(), ToString::to_string)
+    }
+
+    // Every key for `action`, "enter/space", for the help overlay.
+    pub fn label(&self, action: Action) -> String {
+        let keys: Vec<String> = self
+            .bindings(action)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        keys.join("/")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct KeyBinding {
+    code: KeyCode,
+    ctrl: bool,
+    alt: bool,
+}
+
+impl KeyBinding {
+    // Ctrl and Alt have to match exactly, so Ctrl+N isn't "no". Shift is
+    // ignored since it's how '?' gets typed, and letters match either case.
+    pub fn matches(&self, key: KeyEvent) -> bool {
+        if key.modifiers.contains(KeyModifiers::CONTROL) != self.ctrl
+            || key.modifiers.contains(KeyModifiers::ALT) != self.alt
+        {
+            return false;
+        }
+        match (self.code, key.code) {
+            (KeyCode::Char(bound), KeyCode::Char(pressed)) => {
+                bound.to_lowercase().eq(pressed.to_lowercase())
+            }
+            (bound, pressed) => bound == pressed,
+        }
+    }
+}
+
+// "y", "?", "enter", "ctrl+c", "alt+left", "f1"...
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut ctrl = false;
+        let mut alt = false;
+        let mut rest = text.trim();
+        loop {
+            let lower = rest.to_lowercase();
+            if lower.starts_with("ctrl+") && rest.len() > 5 {
+                ctrl = true;
+                rest = &rest[5..];
+            } else if lower.starts_with("alt+") && rest.len() > 4 {
+                alt = true;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "space" => KeyCode::Char(' '),
+                "esc" | "escape" => KeyCode::Esc,
+                "backspace" => KeyCode::Backspace,
+                "tab" => KeyCode::Tab,
+                "delete" | "del" => KeyCode::Delete,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key {text:?}")),
+                },
+            },
+        };
+        Ok(Self { code, ctrl, alt })
+    }
+}
+
+impl TryFrom<String> for KeyBinding {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            KeyCode::Enter => write!(f, "enter"),
+            KeyCode::Esc => write!(f, "esc"),
+            KeyCode::PageUp => write!(f, "pageup"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            code => write!(f, "{}", format!("{code:?}").to_lowercase()),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+pub mod advent;
+pub mod anniversary;
+pub mod app;
+pub mod background;
+pub mod card;
+pub mod clock;
+pub mod color;
+pub mod dialog;
+pub mod gate;
+pub mod heart;
+pub mod heart3d;
+pub mod hooks;
+pub mod input;
+pub mod keymap;
+pub mod seal;
+pub mod scene;
+pub mod shapes;
+pub mod state;
+pub mod svg;
+pub mod timezones;
+pub mod transition;
+pub mod ui;
+pub mod unlock;
+pub mod vault;
+pub mod widget;
+
+pub use app::{App, CurrentScreen, TICK_RATE};
+pub use card::Card;
+pub use heart::{Bounce, Heart};
+pub use heart3d::Heart3d;
+pub use scene::{Scene, SceneId};
+pub use shapes::{Figure, Motif, Sprite, Transform};
+pub use svg::{Drawing, SvgPath};
+pub use widget::CardWidget;
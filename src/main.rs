@@ -1,31 +1,76 @@
-mod app;
-mod ui;
+mod cli;
 
-use chrono::Local;
 use crossterm::{
-    event::{self, Event, KeyCode},
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use ratatui::{
-    backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Style},
-    widgets::{Block, Borders, Paragraph},
-    Terminal,
+use ratatui::{backend::CrosstermBackend, DefaultTerminal, Terminal};
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
 };
-use std::{io, time::Duration};
-use app::App;
+use zeroize::Zeroizing;
+use valentines_rs::card::{Card, CardFile};
+use valentines_rs::clock::Clock;
+use valentines_rs::state::{self, SavedState};
+use valentines_rs::{gate, seal, unlock, vault, App};
+use cli::Command;
 
 #[tokio::main]
 async fn main() -> Result<(), io::Error> {
+    let (card_file, state_path) = match or_exit(Command::from_args()) {
+        Command::Run { card, reset } => {
+            let state_path = state::path_for_card(card.as_deref());
+            if reset {
+                or_exit(SavedState::reset(&state_path));
+            }
+            (or_exit(CardFile::read(card.as_deref())), state_path)
+        }
+        Command::Seal { text } => {
+            println!("{}", seal::seal(&text));
+            return Ok(());
+        }
+        Command::GateHash { answer } => {
+            let salt = gate::new_salt();
+            println!("salt = \"{}\"", salt);
+            let hash = or_exit(gate::hash_answer(&salt, &answer));
+            println!("hash = \"{}\"", gate::written_hash(&hash));
+            return Ok(());
+        }
+        Command::Encrypt { card, out } => {
+            or_exit(encrypt_card(&card, out.as_deref()));
+            return Ok(());
+        }
+        Command::Decrypt { card, out } => {
+            or_exit(decrypt_card(&card, out.as_deref()));
+            return Ok(());
+        }
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+    };
+    let clock = or_exit(Clock::from_env());
+    let (saved, notice) = or_exit(SavedState::load_or_start_over(&state_path));
+    if let Some(notice) = notice {
+        eprintln!("valentines_rs: {notice}");
+    }
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
-    let app_result = App::new().run(&mut terminal);
+    let app_result = open_card(&mut terminal, card_file).and_then(|card| match card {
+        Some(card) => App::new(card)
+            .with_clock(clock)
+            .with_state(state_path, saved)
+            .run(&mut terminal),
+        None => Ok(()),
+    });
 
     if let Err(err) = ratatui::try_restore() {
         eprintln!("failed to restore or reset terminal, {}", err)
@@ -34,6 +79,73 @@ async fn main() -> Result<(), io::Error> {
     // Main loop
     // Cleanup terminal
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        LeaveAlternateScreen
+    )?;
+    or_exit(app_result);
     Ok(())
 }
+
+// An encrypted card is only opened once the terminal is up for the passphrase
+// prompt, and the App never sees it until it's right. None when they gave up.
+fn open_card(terminal: &mut DefaultTerminal, card_file: CardFile) -> io::Result<Option<Card>> {
+    match card_file {
+        CardFile::Plain(card) => Ok(Some(*card)),
+        CardFile::Encrypted { path, locked } => match unlock::unlock(terminal, &path, &locked)? {
+            Some(text) => Card::parse(&text, &path).map(Some),
+            None => Ok(None),
+        },
+    }
+}
+
+fn encrypt_card(path: &Path, out: Option<&Path>) -> io::Result<()> {
+    let text = fs::read_to_string(path)?;
+    if vault::is_encrypted(&text) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is already encrypted", path.display()),
+        ));
+    }
+    // no point locking away a card that won't open
+    Card::parse(&text, path)?;
+    let passphrase = Zeroizing::new(rpassword::prompt_password("Passphrase: ")?);
+    if passphrase.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "the passphrase is empty"));
+    }
+    let again = Zeroizing::new(rpassword::prompt_password("Once more: ")?);
+    if passphrase != again {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "the passphrases don't match"));
+    }
+    write_out(out, &vault::encrypt(&text, &passphrase)?)
+}
+
+fn decrypt_card(path: &Path, out: Option<&Path>) -> io::Result<()> {
+    let locked = vault::Locked::parse(&fs::read_to_string(path)?)
+        .map_err(|err| io::Error::new(err.kind(), format!("{}: {}", path.display(), err)))?;
+    let passphrase = Zeroizing::new(rpassword::prompt_password("Passphrase: ")?);
+    let text = locked.open(&passphrase)?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "wrong passphrase, or the file was changed",
+        )
+    })?;
+    write_out(out, &text)
+}
+
+fn write_out(out: Option<&Path>, text: &str) -> io::Result<()> {
+    match out {
+        Some(out) => fs::write(out, text),
+        None => io::stdout().write_all(text.as_bytes()),
+    }
+}
+
+// Problems found before the terminal is taken over are reported plainly.
+fn or_exit<T>(result: io::Result<T>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("valentines_rs: {err}");
+        std::process::exit(1);
+    })
+}
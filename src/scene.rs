@@ -0,0 +1,507 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{buffer::Buffer, layout::Rect};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::HashMap, fmt, rc::Rc, sync::Arc};
+
+use crate::advent::SurpriseKind;
+use crate::app::{App, CurrentScreen};
+use crate::input::TextInput;
+use crate::keymap::Action;
+use crate::ui;
+
+// One screen of the card. The built in screens are scenes like any other,
+// so a new one only has to be registered with App::with_scene, nothing in
+// the core needs to know about it.
+pub trait Scene {
+    // `area` is the whole card, header and all, which popups center in.
+    // `main_chunks` is the top half for popups and the bottom half where the
+    // heart bounces, side by side on wide terminals. A scene with a text box
+    // puts the cursor in app.cursor.
+    fn render(&mut self, area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>);
+
+    // Keys, pastes and clicks. `action` is what a key means in the card's
+    // keymap, None for anything else. Quit, help, pause and back are dealt
+    // with before this unless the scene captures them.
+    fn handle_event(&mut self, _app: &mut App, _event: &Event, _action: Option<Action>) {}
+
+    // Called every tick while the scene is showing and nothing is paused.
+    fn on_tick(&mut self, _app: &mut App) {}
+
+    // Whether anything drawn here moves from one tick to the next. A still
+    // scene is only drawn again when something happens or the clock's
+    // second changes.
+    fn animated(&self, _app: &App) -> bool {
+        true
+    }
+
+    fn on_enter(&mut self, _app: &mut App) {}
+
+    fn on_exit(&mut self, _app: &mut App) {}
+
+    // Keys this scene wants before the global ones, like the keys typed
+    // into a text box.
+    fn captures(&self, _app: &App, _key: KeyEvent, _action: Option<Action>) -> bool {
+        false
+    }
+
+    // Whether Back can return here once it's been left.
+    fn revisitable(&self, _app: &App) -> bool {
+        true
+    }
+
+    // What the keys do here, for the help overlay. The global keys are added
+    // after these.
+    fn help(&self, _app: &App) -> Vec<(String, &'static str)> {
+        Vec::new()
+    }
+}
+
+// The name a scene added with App::with_scene goes by, in the state file and
+// in the card's [[lock]] tables.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SceneId(pub Arc<str>);
+
+impl SceneId {
+    pub fn new(name: &str) -> Self {
+        SceneId(name.into())
+    }
+}
+
+impl Serialize for SceneId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SceneId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(SceneId(String::deserialize(deserializer)?.into()))
+    }
+}
+
+// Every scene the app can show, by screen.
+#[derive(Default)]
+pub struct Scenes {
+    scenes: HashMap<CurrentScreen, Box<dyn Scene>>,
+}
+
+impl Scenes {
+    // Replaces whatever was registered for `screen` before.
+    pub fn register(&mut self, screen: CurrentScreen, scene: impl Scene + 'static) {
+        self.scenes.insert(screen, Box::new(scene));
+    }
+
+    pub fn contains(&self, screen: &CurrentScreen) -> bool {
+        self.scenes.contains_key(screen)
+    }
+
+    pub fn get(&self, screen: &CurrentScreen) -> Option<&dyn Scene> {
+        self.scenes.get(screen).map(|scene| scene.as_ref())
+    }
+
+    // A scene is taken out while it runs, so it can have the app mutably,
+    // and put back after.
+    pub fn take(&mut self, screen: &CurrentScreen) -> Option<Box<dyn Scene>> {
+        self.scenes.remove(screen)
+    }
+
+    pub fn put_back(&mut self, screen: CurrentScreen, scene: Box<dyn Scene>) {
+        self.scenes.entry(screen).or_insert(scene);
+    }
+}
+
+impl fmt::Debug for Scenes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.scenes.keys()).finish()
+    }
+}
+
+// The card's own screens, registered the same way anyone else's would be.
+pub fn built_in(app: App) -> App {
+    app.with_scene(CurrentScreen::Gate, Gate)
+        .with_scene(CurrentScreen::IntroScreen, Intro)
+        .with_scene(CurrentScreen::Advent, Advent)
+        .with_scene(CurrentScreen::WillYouBeMyValentine, Question)
+        .with_scene(CurrentScreen::NotValentine, WrongAnswer)
+        .with_scene(CurrentScreen::YesValentine, RightAnswer)
+        .with_scene(CurrentScreen::Rsvp, Rsvp)
+        .with_scene(CurrentScreen::DateChoice, DateChoice)
+        .with_scene(CurrentScreen::DateInfo, DateInfo)
+        .with_scene(CurrentScreen::Itinerary, Itinerary)
+        .with_scene(CurrentScreen::Anniversary, Anniversary)
+        .with_scene(CurrentScreen::Letters, Letters)
+        .with_scene(CurrentScreen::Celebration, Celebration)
+}
+
+fn key(event: &Event) -> Option<KeyEvent> {
+    match event {
+        Event::Key(key) => Some(*key),
+        _ => None,
+    }
+}
+
+// Every plain key is typed, but ctrl and alt ones still reach the keymap
+// unless the text box edits with them, so ctrl+c quits from anywhere.
+fn typing(key: KeyEvent) -> bool {
+    let held = key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+    !held || TextInput::shortcut(key)
+}
+
+// The question can't be taken back once it's a yes, so nothing up to it
+// can be revisited after that.
+fn before_yes(app: &App) -> bool {
+    !app.answer.as_ref().is_some_and(|answer| answer.accepted)
+}
+
+struct Gate;
+
+impl Scene for Gate {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+        ui::render_gate_screen(area, buf, app, main_chunks);
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &Event, _action: Option<Action>) {
+        match event {
+            Event::Key(key) => app.handle_gate_event(*key),
+            Event::Paste(text) => app.gate_input.insert_str(text),
+            _ => {}
+        }
+    }
+
+    // the answer is typed, so every key belongs to it
+    fn captures(&self, _app: &App, key: KeyEvent, _action: Option<Action>) -> bool {
+        typing(key)
+    }
+
+    // it's asked again every time the card opens
+    fn revisitable(&self, _app: &App) -> bool {
+        false
+    }
+
+    fn animated(&self, _app: &App) -> bool {
+        false
+    }
+}
+
+struct Intro;
+
+impl Scene for Intro {
+    fn render(&mut self, _area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+        ui::render_intro_screens(buf, app, main_chunks);
+    }
+
+    fn handle_event(&mut self, app: &mut App, _event: &Event, action: Option<Action>) {
+        if action == Some(Action::Advance) {
+            app.next_intro_screen();
+        }
+    }
+
+    fn on_exit(&mut self, app: &mut App) {
+        app.reset_heart();
+    }
+
+    fn help(&self, app: &App) -> Vec<(String, &'static str)> {
+        vec![(app.card.keys.label(Action::Advance), "next page")]
+    }
+
+    fn revisitable(&self, app: &App) -> bool {
+        before_yes(app)
+    }
+
+    fn animated(&self, _app: &App) -> bool {
+        false
+    }
+}
+
+struct Advent;
+
+impl Scene for Advent {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+        ui::render_advent_screen(area, buf, app, main_chunks);
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &Event, action: Option<Action>) {
+        if let Some(key) = key(event) {
+            app.handle_advent_event(key, action);
+        }
+    }
+
+    fn on_enter(&mut self, app: &mut App) {
+        app.advent_cursor = app.advent_today();
+    }
+
+    fn on_exit(&mut self, app: &mut App) {
+        app.advent_open = None;
+    }
+
+    // back closes an open surprise before it leaves the calendar, and the
+    // calendar moves with the arrows
+    fn captures(&self, app: &App, key: KeyEvent, action: Option<Action>) -> bool {
+        action == Some(Action::Back)
+            && (app.advent_open.is_some()
+                || matches!(key.code, KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down))
+    }
+
+    fn help(&self, app: &App) -> Vec<(String, &'static str)> {
+        let advance = app.card.keys.label(Action::Advance);
+        match app.advent_open {
+            Some(_) => vec![(advance, "close the surprise")],
+            None => vec![
+                ("arrows/hjkl".into(), "move between days"),
+                (advance, "open today's surprise"),
+            ],
+        }
+    }
+
+    fn revisitable(&self, app: &App) -> bool {
+        before_yes(app)
+    }
+
+    // only the heart of an animation surprise beats
+    fn animated(&self, app: &App) -> bool {
+        let open = app.advent_open.zip(app.card.advent.as_ref());
+        open.and_then(|(open, advent)| advent.surprise(open))
+            .is_some_and(|surprise| surprise.kind == SurpriseKind::Animation)
+    }
+}
+
+struct Question;
+
+impl Scene for Question {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+        ui::render_valentine_screen(area, buf, app, main_chunks);
+    }
+
+    fn handle_event(&mut self, app: &mut App, _event: &Event, action: Option<Action>) {
+        app.handle_wybmv_event(action);
+    }
+
+    fn help(&self, app: &App) -> Vec<(String, &'static str)> {
+        vec![
+            (app.card.keys.label(Action::Yes), "yes!"),
+            (app.card.keys.label(Action::No), "no (think carefully)"),
+        ]
+    }
+
+    fn revisitable(&self, app: &App) -> bool {
+        before_yes(app)
+    }
+}
+
+struct WrongAnswer;
+
+impl Scene for WrongAnswer {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+        ui::render_no_screen(area, buf, app, main_chunks);
+    }
+
+    fn handle_event(&mut self, app: &mut App, _event: &Event, action: Option<Action>) {
+        app.handle_notvalentine_event(action);
+    }
+
+    fn on_exit(&mut self, app: &mut App) {
+        app.reset_heart();
+    }
+
+    fn help(&self, app: &App) -> Vec<(String, &'static str)> {
+        vec![(app.card.keys.label(Action::Advance), "try again")]
+    }
+
+    fn revisitable(&self, _app: &App) -> bool {
+        false
+    }
+}
+
+struct RightAnswer;
+
+impl Scene for RightAnswer {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+        ui::render_yes_screen(area, buf, app, main_chunks);
+    }
+
+    fn handle_event(&mut self, app: &mut App, _event: &Event, action: Option<Action>) {
+        app.handle_yesvalentine_event(action);
+    }
+
+    fn on_exit(&mut self, app: &mut App) {
+        app.reset_heart();
+    }
+
+    fn help(&self, app: &App) -> Vec<(String, &'static str)> {
+        vec![
+            (app.card.keys.label(Action::Yes), "on to the plans"),
+            (app.card.keys.label(Action::No), "back to the question"),
+        ]
+    }
+}
+
+struct Rsvp;
+
+impl Scene for Rsvp {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+        ui::render_rsvp_screen(area, buf, app, main_chunks);
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &Event, _action: Option<Action>) {
+        match event {
+            Event::Key(key) => app.handle_rsvp_event(*key),
+            Event::Paste(text) => app.rsvp_input.insert_str(text),
+            _ => {}
+        }
+    }
+
+    // typing a note needs every key, including 'q'
+    fn captures(&self, _app: &App, key: KeyEvent, _action: Option<Action>) -> bool {
+        typing(key)
+    }
+}
+
+struct DateChoice;
+
+impl Scene for DateChoice {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+        ui::render_datechoice_screen(area, buf, app, main_chunks);
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &Event, action: Option<Action>) {
+        match event {
+            Event::Key(key) => app.handle_datechoice_event(*key, action),
+            Event::Mouse(mouse) => app.handle_mouse_event(*mouse),
+            _ => {}
+        }
+    }
+
+    fn help(&self, app: &App) -> Vec<(String, &'static str)> {
+        vec![
+            ("up/down".into(), "look through the plans"),
+            (app.card.keys.label(Action::Advance), "pick this one"),
+        ]
+    }
+}
+
+struct DateInfo;
+
+impl Scene for DateInfo {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+        ui::render_dateinfo_screen(area, buf, app, main_chunks);
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &Event, _action: Option<Action>) {
+        if let Some(key) = key(event) {
+            app.handle_dateinfo_event(key);
+        }
+    }
+
+    fn help(&self, app: &App) -> Vec<(String, &'static str)> {
+        let mut entries = Vec::new();
+        if !app.card.itinerary.is_empty() {
+            entries.push(("i".into(), "our itinerary"));
+        }
+        if app.card.anniversary.is_some() {
+            entries.push(("a".into(), "our days together"));
+        }
+        if !app.card.letters.is_empty() {
+            entries.push(("l".into(), "your letters"));
+        }
+        entries
+    }
+}
+
+struct Itinerary;
+
+impl Scene for Itinerary {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+        ui::render_itinerary_screen(area, buf, app, main_chunks);
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &Event, action: Option<Action>) {
+        if let Some(key) = key(event) {
+            app.handle_itinerary_event(key, action);
+        }
+    }
+
+    fn on_tick(&mut self, app: &mut App) {
+        app.follow_itinerary();
+    }
+
+    fn on_enter(&mut self, app: &mut App) {
+        app.follow_itinerary();
+    }
+
+    fn help(&self, _app: &App) -> Vec<(String, &'static str)> {
+        vec![("i/esc".into(), "back to the date")]
+    }
+}
+
+struct Anniversary;
+
+impl Scene for Anniversary {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+        ui::render_anniversary_screen(area, buf, app, main_chunks);
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &Event, action: Option<Action>) {
+        if let Some(key) = key(event) {
+            app.handle_anniversary_event(key, action);
+        }
+    }
+
+    fn help(&self, _app: &App) -> Vec<(String, &'static str)> {
+        vec![("a/esc".into(), "back to the date")]
+    }
+}
+
+struct Letters;
+
+impl Scene for Letters {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+        ui::render_letters_screen(area, buf, app, main_chunks);
+    }
+
+    fn handle_event(&mut self, app: &mut App, event: &Event, action: Option<Action>) {
+        if let Some(key) = key(event) {
+            app.handle_letters_event(key, action);
+        }
+    }
+
+    fn help(&self, _app: &App) -> Vec<(String, &'static str)> {
+        vec![
+            ("up/down".into(), "pick a letter"),
+            ("l/esc".into(), "back to the date"),
+        ]
+    }
+}
+
+struct Celebration;
+
+impl Scene for Celebration {
+    fn render(&mut self, _area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+        ui::render_celebration_screen(buf, app, main_chunks);
+    }
+
+    fn handle_event(&mut self, app: &mut App, _event: &Event, action: Option<Action>) {
+        if action == Some(Action::Advance) {
+            app.go_to(CurrentScreen::DateInfo);
+        }
+    }
+
+    fn on_tick(&mut self, app: &mut App) {
+        if app.tick_count >= app.celebrate_until {
+            app.go_to(CurrentScreen::DateInfo);
+        }
+    }
+
+    fn on_enter(&mut self, app: &mut App) {
+        app.celebrate();
+    }
+
+    fn help(&self, app: &App) -> Vec<(String, &'static str)> {
+        vec![(app.card.keys.label(Action::Advance), "back to the date")]
+    }
+
+    // it only happens once
+    fn revisitable(&self, _app: &App) -> bool {
+        false
+    }
+}
+
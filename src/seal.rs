@@ -0,0 +1,59 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Deserializer};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const PREFIX: &str = "sealed:";
+
+// Text kept scrambled in the card file so a peek at the file (or a grep)
+// doesn't spoil it. This is not encryption: anyone with this binary can open
+// it, it only keeps the surprise until the unlock time.
+#[derive(Debug, Clone, Default)]
+pub struct Sealed(String);
+
+impl Sealed {
+    pub fn open(&self) -> String {
+        unseal(&self.0).unwrap_or_else(|| self.0.clone())
+    }
+}
+
+impl<'de> Deserialize<'de> for Sealed {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(de)?;
+        if text.starts_with(PREFIX) && unseal(&text).is_none() {
+            return Err(serde::de::Error::custom("sealed text is damaged"));
+        }
+        Ok(Sealed(text))
+    }
+}
+
+pub fn seal(text: &str) -> String {
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0x5eed, |d| d.as_nanos() as u64);
+    let mut bytes = nonce.to_le_bytes().to_vec();
+    bytes.extend(text.bytes().zip(keystream(nonce)).map(|(b, k)| b ^ k));
+    format!("{PREFIX}{}", URL_SAFE_NO_PAD.encode(bytes))
+}
+
+// Plain text (without the prefix) is passed through unchanged.
+pub fn unseal(text: &str) -> Option<String> {
+    let Some(encoded) = text.strip_prefix(PREFIX) else {
+        return Some(text.to_string());
+    };
+    let bytes = URL_SAFE_NO_PAD.decode(encoded.trim()).ok()?;
+    let (nonce, body) = bytes.split_at_checked(8)?;
+    let nonce = u64::from_le_bytes(nonce.try_into().ok()?);
+    let plain = body.iter().zip(keystream(nonce)).map(|(b, k)| b ^ k).collect();
+    String::from_utf8(plain).ok()
+}
+
+// xorshift64*, seeded per message so the same text never seals the same way
+fn keystream(seed: u64) -> impl Iterator<Item = u8> {
+    let mut state = seed | 1;
+    std::iter::repeat_with(move || {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        (state.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 56) as u8
+    })
+}
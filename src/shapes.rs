@@ -0,0 +1,290 @@
+use ratatui::{
+    style::Color,
+    widgets::canvas::{Painter, Shape},
+};
+use serde::Deserialize;
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use crate::svg::SvgPath;
+
+// A point of a traced line, where it is on the canvas and which dot that is,
+// if it's on the canvas at all.
+pub type Sample = (f64, f64, Option<(usize, usize)>);
+
+// Steps along a line never get bigger than this, so it still comes out the
+// right shape where it's off the canvas.
+const MAX_STEP: f64 = 1.0 / 64.0;
+// or smaller than this, where it runs along the edge of the canvas
+const MIN_STEP: f64 = 1.0 / 65536.0;
+
+// Walks `point` from 0 to 1 in steps that land each point on a dot next to
+// the last one, so a big shape has no gaps and a small one isn't painted over
+// and over.
+pub fn trace(painter: &Painter<'_, '_>, point: impl Fn(f64) -> (f64, f64)) -> Vec<Sample> {
+    let sample = |t: f64| {
+        let (x, y) = point(t);
+        (x, y, painter.get_point(x, y))
+    };
+    let mut samples = vec![sample(0.0)];
+    let (mut t, mut step) = (0.0, MAX_STEP);
+    while t < 1.0 {
+        let next = (t + step).min(1.0);
+        let (x, y, dot) = sample(next);
+        let last = samples[samples.len() - 1].2;
+        match (last, dot) {
+            (Some(a), Some(b)) => {
+                let gap = a.0.abs_diff(b.0).max(a.1.abs_diff(b.1));
+                if gap > 1 && step > MIN_STEP {
+                    step /= 2.0;
+                    continue;
+                }
+                if gap == 0 {
+                    step = (step * 2.0).min(MAX_STEP);
+                    t = next;
+                    continue;
+                }
+            }
+            _ => step = (step * 2.0).min(MAX_STEP),
+        }
+        t = next;
+        samples.push((x, y, dot));
+    }
+    samples
+}
+
+// Where a shape goes on the canvas. Shapes are drawn about -1 to 1 each way,
+// so `scale` is half the size they come out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub x: f64,
+    pub y: f64,
+    pub scale: f64,
+    // anticlockwise, in radians
+    pub rotation: f64,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            scale: 1.0,
+            rotation: 0.0,
+        }
+    }
+}
+
+impl Transform {
+    pub fn new(x: f64, y: f64, scale: f64) -> Self {
+        Self {
+            x,
+            y,
+            scale,
+            rotation: 0.0,
+        }
+    }
+
+    pub fn rotated(self, rotation: f64) -> Self {
+        Self { rotation, ..self }
+    }
+
+    pub fn apply(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let (sin, cos) = self.rotation.sin_cos();
+        (
+            self.scale.mul_add(x * cos - y * sin, self.x),
+            self.scale.mul_add(x * sin + y * cos, self.y),
+        )
+    }
+}
+
+// The shapes a card can draw with, written in the card as "star",
+// { rose = { petals = 5 } } and so on.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Motif {
+    #[default]
+    Heart,
+    Rose {
+        petals: u8,
+    },
+    Star {
+        points: u8,
+    },
+    // cupid's, pointing right
+    Arrow,
+    Ring,
+    Envelope,
+    Flower {
+        petals: u8,
+    },
+    Infinity,
+}
+
+impl Motif {
+    // Every motif, with a sensible number of petals and points.
+    pub const ALL: [Motif; 8] = [
+        Motif::Heart,
+        Motif::Rose { petals: 5 },
+        Motif::Star { points: 5 },
+        Motif::Arrow,
+        Motif::Ring,
+        Motif::Envelope,
+        Motif::Flower { petals: 6 },
+        Motif::Infinity,
+    ];
+
+    // How many separate lines it takes to draw.
+    pub fn strokes(self) -> usize {
+        match self {
+            Motif::Arrow => 4,
+            Motif::Ring | Motif::Envelope | Motif::Flower { .. } => 2,
+            _ => 1,
+        }
+    }
+
+    // Where `stroke` is `t` of the way along it, from 0 to 1.
+    pub fn point(self, stroke: usize, t: f64) -> (f64, f64) {
+        let angle = t * TAU;
+        match (self, stroke) {
+            (Motif::Heart, _) => {
+                let x = 16.0 * angle.sin().powi(3);
+                let y = 13.0 * angle.cos()
+                    - 5.0 * (2.0 * angle).cos()
+                    - 2.0 * (3.0 * angle).cos()
+                    - (4.0 * angle).cos();
+                (x / 17.0, y / 17.0)
+            }
+            // r = |cos(nθ/2)| has n petals whether n is odd or even
+            (Motif::Rose { petals }, _) => {
+                let r = (f64::from(petals) * angle / 2.0).cos().abs();
+                polar(r, angle)
+            }
+            (Motif::Star { points }, _) => {
+                let points = usize::from(points.max(2));
+                let corners: Vec<(f64, f64)> = (0..=2 * points)
+                    .map(|i| {
+                        let r = if i % 2 == 0 { 1.0 } else { 0.45 };
+                        polar(r, FRAC_PI_2 + i as f64 * PI / points as f64)
+                    })
+                    .collect();
+                along(&corners, t)
+            }
+            (Motif::Arrow, 0) => along(&[(-1.0, 0.0), (1.0, 0.0)], t),
+            (Motif::Arrow, 1) => along(&[(0.7, 0.25), (1.0, 0.0), (0.7, -0.25)], t),
+            (Motif::Arrow, 2) => along(&[(-1.0, 0.2), (-0.8, 0.0), (-1.0, -0.2)], t),
+            (Motif::Arrow, _) => along(&[(-0.85, 0.2), (-0.65, 0.0), (-0.85, -0.2)], t),
+            (Motif::Ring, 0) => {
+                let (x, y) = polar(0.7, angle);
+                (x, y - 0.3)
+            }
+            // the stone on top
+            (Motif::Ring, _) => along(
+                &[(0.0, 0.4), (0.25, 0.65), (0.0, 0.95), (-0.25, 0.65), (0.0, 0.4)],
+                t,
+            ),
+            (Motif::Envelope, 0) => along(
+                &[(-1.0, 0.65), (-1.0, -0.65), (1.0, -0.65), (1.0, 0.65), (-1.0, 0.65)],
+                t,
+            ),
+            // the flap
+            (Motif::Envelope, _) => along(&[(-1.0, 0.65), (0.0, -0.1), (1.0, 0.65)], t),
+            (Motif::Flower { petals }, 0) => {
+                let r = 0.6 + 0.4 * (f64::from(petals) * angle).cos();
+                polar(r, angle)
+            }
+            (Motif::Flower { .. }, _) => polar(0.2, angle),
+            // the lemniscate of Bernoulli
+            (Motif::Infinity, _) => {
+                let (sin, cos) = angle.sin_cos();
+                let scale = 1.0 + sin * sin;
+                (cos / scale, sin * cos / scale)
+            }
+        }
+    }
+}
+
+fn polar(r: f64, angle: f64) -> (f64, f64) {
+    let (sin, cos) = angle.sin_cos();
+    (r * cos, r * sin)
+}
+
+// `t` of the way along the lines joining `corners`, each line taking an equal
+// share of `t`.
+fn along(corners: &[(f64, f64)], t: f64) -> (f64, f64) {
+    let lines = corners.len() - 1;
+    let at = t.clamp(0.0, 1.0) * lines as f64;
+    let line = (at as usize).min(lines - 1);
+    let ((x0, y0), (x1, y1)) = (corners[line], corners[line + 1]);
+    let part = at - line as f64;
+    (x0 + (x1 - x0) * part, y0 + (y1 - y0) * part)
+}
+
+// What a card can put where the heart goes: one of the motifs, a drawing of
+// its own as SVG path data, written { path = "M 0 0 L 10 10" }, or
+// "spinning_heart", which doesn't bounce but turns in 3D in the middle.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "SpriteText")]
+pub enum Sprite {
+    Motif(Motif),
+    Drawing(SvgPath),
+    SpinningHeart,
+}
+
+impl Default for Sprite {
+    fn default() -> Self {
+        Sprite::Motif(Motif::Heart)
+    }
+}
+
+// How a sprite is written in the card, before any path in it is read, so a
+// mistake in the path gets its own error.
+#[derive(Deserialize)]
+#[serde(
+    untagged,
+    expecting = "a shape like \"heart\", \"spinning_heart\", { star = { points = 5 } } or \
+                 { path = \"M 0 0 L 9 9\" }"
+)]
+enum SpriteText {
+    Motif(Motif),
+    Drawing { path: String },
+    Named(Named),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Named {
+    SpinningHeart,
+}
+
+impl TryFrom<SpriteText> for Sprite {
+    type Error = String;
+
+    fn try_from(text: SpriteText) -> Result<Self, Self::Error> {
+        match text {
+            SpriteText::Motif(motif) => Ok(Sprite::Motif(motif)),
+            SpriteText::Drawing { path } => Ok(Sprite::Drawing(path.parse()?)),
+            SpriteText::Named(Named::SpinningHeart) => Ok(Sprite::SpinningHeart),
+        }
+    }
+}
+
+// A motif in one colour, placed on a canvas.
+#[derive(Debug, Default, Clone)]
+pub struct Figure {
+    pub motif: Motif,
+    pub transform: Transform,
+    pub color: Color,
+}
+
+impl Shape for Figure {
+    fn draw(&self, painter: &mut Painter<'_, '_>) {
+        for stroke in 0..self.motif.strokes() {
+            let line = trace(painter, |t| self.transform.apply(self.motif.point(stroke, t)));
+            for (_, _, dot) in line {
+                if let Some((x, y)) = dot {
+                    painter.paint(x, y, self.color);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::app::{Answer, CurrentScreen, IntroScreenOptions, Visit};
+
+// Bump when a saved field changes meaning. Files from before versions existed
+// read as 0 and only ever had fields that still mean the same thing.
+pub const STATE_VERSION: u32 = 1;
+
+// What the recipient has done with the card, kept between runs.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedState {
+    pub version: u32,
+    // where they were, so reopening the card picks up from there
+    pub screen: Option<CurrentScreen>,
+    pub intro_screen: IntroScreenOptions,
+    pub history: Vec<Visit>,
+    pub answer: Option<Answer>,
+    pub no_count: u32,
+    pub opened_days: BTreeSet<NaiveDate>,
+    // wrong answers at the gate, so quitting and reopening or --reset
+    // doesn't buy more guesses. Deleting the file does: it's a speed bump
+    // for someone with the card, the hash is what keeps the answer safe.
+    pub gate_attempts: u32,
+    // the [on_arrival] hooks have run, so they never run twice
+    pub hooks_fired: bool,
+}
+
+impl SavedState {
+    // A missing file is a fresh start.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err),
+        };
+        let state: Self = toml::from_str(&text).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), err),
+            )
+        })?;
+        if state.version > STATE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{}: saved by a newer version (state version {}, this one reads {})",
+                    path.display(),
+                    state.version,
+                    STATE_VERSION
+                ),
+            ));
+        }
+        Ok(state)
+    }
+
+    // A corrupt state file, or one from a newer version, shouldn't keep the
+    // card shut. It is moved out of the way, not deleted, and the card starts
+    // over. Anything else, like not being allowed to read it, is still an
+    // error. The notice saying what happened is for the caller to show.
+    pub fn load_or_start_over(path: &Path) -> io::Result<(Self, Option<String>)> {
+        match Self::load(path) {
+            Ok(state) => Ok((state, None)),
+            Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+                let mut bad = path.as_os_str().to_owned();
+                bad.push(".bad");
+                let notice = match fs::rename(path, &bad) {
+                    Ok(()) => format!(
+                        "{err}\nstarting over, the old progress is in {}",
+                        Path::new(&bad).display()
+                    ),
+                    // still not worth keeping the card shut over
+                    Err(rename_err) => format!(
+                        "{err}\nstarting over, and couldn't move it to {}: {}",
+                        Path::new(&bad).display(),
+                        rename_err
+                    ),
+                };
+                Ok((Self::default(), Some(notice)))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    // --reset: forget everything and start the card from the top, all but
+    // the wrong guesses at the gate.
+    pub fn reset(path: &Path) -> io::Result<()> {
+        let gate_attempts = Self::load(path).map_or(0, |state| state.gate_attempts);
+        if gate_attempts > 0 {
+            return Self { gate_attempts, ..Self::default() }.save(path);
+        }
+        match fs::remove_file(path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    // Written next to the real file and renamed over it, so quitting halfway
+    // through a save can't leave half a file behind.
+    pub fn save(&mut self, path: &Path) -> io::Result<()> {
+        self.version = STATE_VERSION;
+        let text = toml::to_string(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        fs::write(&tmp, text)?;
+        fs::rename(&tmp, path)
+    }
+}
+
+// card.toml keeps its progress in card.state.toml, the built in card in
+// valentine.state.toml in the working directory.
+pub fn path_for_card(card: Option<&Path>) -> PathBuf {
+    match card {
+        Some(card) => card.with_extension("state.toml"),
+        None if Path::new(crate::card::DEFAULT_CARD_PATH).exists() => {
+            Path::new(crate::card::DEFAULT_CARD_PATH).with_extension("state.toml")
+        }
+        None => PathBuf::from("valentine.state.toml"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a fresh directory per test, under the system temp dir
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("valentines_rs-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn missing_file_starts_fresh() {
+        let dir = scratch("missing");
+        let (state, notice) = SavedState::load_or_start_over(&dir.join("card.state.toml")).unwrap();
+        assert_eq!(state.no_count, 0);
+        assert!(notice.is_none());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn corrupt_file_is_moved_aside() {
+        let dir = scratch("corrupt");
+        let path = dir.join("card.state.toml");
+        fs::write(&path, "no_count = \"lots\"").unwrap();
+        let (state, notice) = SavedState::load_or_start_over(&path).unwrap();
+        assert_eq!(state.no_count, 0);
+        assert!(notice.unwrap().contains("card.state.toml.bad"));
+        assert!(!path.exists());
+        let bad = fs::read_to_string(dir.join("card.state.toml.bad")).unwrap();
+        assert_eq!(bad, "no_count = \"lots\"");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn unreadable_path_is_an_error() {
+        // a directory where the file should be isn't corrupt progress, and
+        // isn't moved
+        let dir = scratch("directory");
+        let path = dir.join("card.state.toml");
+        fs::create_dir(&path).unwrap();
+        assert!(SavedState::load_or_start_over(&path).is_err());
+        assert!(path.is_dir());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn saved_state_loads_back() {
+        let dir = scratch("round-trip");
+        let path = dir.join("card.state.toml");
+        let mut state = SavedState { no_count: 3, gate_attempts: 2, ..SavedState::default() };
+        state.save(&path).unwrap();
+        let (state, notice) = SavedState::load_or_start_over(&path).unwrap();
+        assert_eq!((state.no_count, state.gate_attempts), (3, 2));
+        assert!(notice.is_none());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,441 @@
+use ratatui::{
+    style::Color,
+    widgets::canvas::{Painter, Shape},
+};
+use std::{
+    f64::consts::{PI, TAU},
+    str::FromStr,
+};
+
+use crate::shapes::{trace, Transform};
+
+// Straight lines per bézier curve, and the most an arc turns in one line.
+const CURVE_LINES: usize = 24;
+const ARC_TURN: f64 = PI / 24.0;
+
+// A drawing from the `d` of an SVG <path>, flattened to straight lines and
+// fitted about -1 to 1 each way with y up, the same as the motifs. Each
+// "M" starts a new stroke.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgPath {
+    strokes: Vec<Vec<(f64, f64)>>,
+}
+
+impl SvgPath {
+    pub fn strokes(&self) -> &[Vec<(f64, f64)>] {
+        &self.strokes
+    }
+
+    // Squeezes the drawing into -1 to 1 without stretching it, and turns it
+    // the right way up, as SVG counts y downwards.
+    fn fit(mut self) -> Result<Self, String> {
+        let points = || self.strokes.iter().flatten();
+        let left = points().map(|p| p.0).fold(f64::MAX, f64::min);
+        let right = points().map(|p| p.0).fold(f64::MIN, f64::max);
+        let top = points().map(|p| p.1).fold(f64::MAX, f64::min);
+        let bottom = points().map(|p| p.1).fold(f64::MIN, f64::max);
+        if left > right {
+            return Err("the path doesn't draw anything".into());
+        }
+        let size = (right - left).max(bottom - top);
+        let scale = if size > 0.0 { 2.0 / size } else { 1.0 };
+        let (middle_x, middle_y) = ((left + right) / 2.0, (top + bottom) / 2.0);
+        for (x, y) in self.strokes.iter_mut().flatten() {
+            *x = (*x - middle_x) * scale;
+            *y = (middle_y - *y) * scale;
+        }
+        Ok(self)
+    }
+}
+
+// "M 10 30 A 20 20 0 0 1 50 30 Q 90 60 50 90 Z" and the like. Every command
+// is understood, absolute and relative.
+impl FromStr for SvgPath {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut tokens = Tokens { text: text.as_bytes(), at: 0 };
+        let mut pen = Pen::default();
+        let mut command = None;
+        while let Some(next) = tokens.command(command)? {
+            if pen.strokes.is_empty() && !matches!(next, b'M' | b'm') {
+                return Err("a path starts with M".into());
+            }
+            let relative = next.is_ascii_lowercase();
+            let (x0, y0) = pen.at;
+            let offset = |(x, y): (f64, f64)| if relative { (x + x0, y + y0) } else { (x, y) };
+            match next.to_ascii_uppercase() {
+                b'M' => {
+                    pen.move_to(offset(tokens.pair()?));
+                    // more pairs after a move are lines
+                    command = Some(if relative { b'l' } else { b'L' });
+                    continue;
+                }
+                b'L' => pen.line_to(offset(tokens.pair()?)),
+                b'H' => {
+                    let x = tokens.number()?;
+                    pen.line_to((if relative { x + x0 } else { x }, y0));
+                }
+                b'V' => {
+                    let y = tokens.number()?;
+                    pen.line_to((x0, if relative { y + y0 } else { y }));
+                }
+                b'C' => {
+                    let (c1, c2) = (offset(tokens.pair()?), offset(tokens.pair()?));
+                    pen.cubic(c1, c2, offset(tokens.pair()?));
+                }
+                b'S' => {
+                    let c1 = pen.reflect(b"CS");
+                    let c2 = offset(tokens.pair()?);
+                    pen.cubic(c1, c2, offset(tokens.pair()?));
+                }
+                b'Q' => {
+                    let c = offset(tokens.pair()?);
+                    pen.quadratic(c, offset(tokens.pair()?));
+                }
+                b'T' => {
+                    let c = pen.reflect(b"QT");
+                    pen.quadratic(c, offset(tokens.pair()?));
+                }
+                b'A' => {
+                    let radii = (tokens.number()?, tokens.number()?);
+                    let rotation = tokens.number()?;
+                    let (large, sweep) = (tokens.flag()?, tokens.flag()?);
+                    pen.arc(radii, rotation, large, sweep, offset(tokens.pair()?));
+                }
+                b'Z' => pen.close(),
+                other => return Err(format!("unknown path command {:?}", char::from(other))),
+            }
+            pen.last = next.to_ascii_uppercase();
+            command = Some(next);
+        }
+        Self { strokes: pen.strokes }.fit()
+    }
+}
+
+impl TryFrom<String> for SvgPath {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+// Reads path data a command or number at a time. Numbers can run together
+// wherever that's unambiguous, as in "M1-2.5.5.5".
+struct Tokens<'a> {
+    text: &'a [u8],
+    at: usize,
+}
+
+impl Tokens<'_> {
+    fn skip(&mut self) {
+        while self.at < self.text.len() && b" \t\r\n,".contains(&self.text[self.at]) {
+            self.at += 1;
+        }
+    }
+
+    // The next command letter, or the last one again when more numbers
+    // follow it. None at the end.
+    fn command(&mut self, last: Option<u8>) -> Result<Option<u8>, String> {
+        self.skip();
+        match self.text.get(self.at) {
+            None => Ok(None),
+            Some(c) if c.is_ascii_alphabetic() => {
+                self.at += 1;
+                Ok(Some(*c))
+            }
+            Some(_) => match last {
+                Some(b'Z' | b'z') | None => Err(format!("expected a command at {}", self.at)),
+                last => Ok(last),
+            },
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, String> {
+        self.skip();
+        let start = self.at;
+        let digits = |at: &mut usize, text: &[u8]| {
+            while *at < text.len() && text[*at].is_ascii_digit() {
+                *at += 1;
+            }
+        };
+        if matches!(self.text.get(self.at), Some(b'+' | b'-')) {
+            self.at += 1;
+        }
+        digits(&mut self.at, self.text);
+        if self.text.get(self.at) == Some(&b'.') {
+            self.at += 1;
+            digits(&mut self.at, self.text);
+        }
+        if matches!(self.text.get(self.at), Some(b'e' | b'E')) {
+            self.at += 1;
+            if matches!(self.text.get(self.at), Some(b'+' | b'-')) {
+                self.at += 1;
+            }
+            digits(&mut self.at, self.text);
+        }
+        std::str::from_utf8(&self.text[start..self.at])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .ok_or_else(|| format!("expected a number at {start}"))
+    }
+
+    fn pair(&mut self) -> Result<(f64, f64), String> {
+        Ok((self.number()?, self.number()?))
+    }
+
+    // Arc flags are a single 0 or 1, and often written with no gap after.
+    fn flag(&mut self) -> Result<bool, String> {
+        self.skip();
+        let flag = match self.text.get(self.at) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(format!("expected 0 or 1 at {}", self.at)),
+        };
+        self.at += 1;
+        Ok(flag)
+    }
+}
+
+// Where the path has got to, and the lines drawn so far.
+#[derive(Default)]
+struct Pen {
+    strokes: Vec<Vec<(f64, f64)>>,
+    at: (f64, f64),
+    start: (f64, f64),
+    // the last command and its last control point, for S and T to mirror
+    last: u8,
+    control: (f64, f64),
+    // a Z has just closed the stroke
+    closed: bool,
+}
+
+impl Pen {
+    fn move_to(&mut self, to: (f64, f64)) {
+        self.strokes.push(vec![to]);
+        self.at = to;
+        self.start = to;
+        self.last = b'M';
+        self.closed = false;
+    }
+
+    fn line_to(&mut self, to: (f64, f64)) {
+        if self.strokes.is_empty() || self.closed {
+            // drawing on after a Z starts again from where it closed
+            self.strokes.push(vec![self.at]);
+            self.closed = false;
+        }
+        if let Some(stroke) = self.strokes.last_mut() {
+            stroke.push(to);
+        }
+        self.at = to;
+        self.control = to;
+    }
+
+    // The last control point mirrored through the pen, if the last command
+    // was one of `after`, otherwise the pen itself.
+    fn reflect(&self, after: &[u8]) -> (f64, f64) {
+        if after.contains(&self.last) {
+            (2.0 * self.at.0 - self.control.0, 2.0 * self.at.1 - self.control.1)
+        } else {
+            self.at
+        }
+    }
+
+    fn cubic(&mut self, c1: (f64, f64), c2: (f64, f64), to: (f64, f64)) {
+        let from = self.at;
+        for i in 1..=CURVE_LINES {
+            let t = i as f64 / CURVE_LINES as f64;
+            let s = 1.0 - t;
+            let blend = |a: f64, b: f64, c: f64, d: f64| {
+                s * s * s * a + 3.0 * s * s * t * b + 3.0 * s * t * t * c + t * t * t * d
+            };
+            self.line_to((blend(from.0, c1.0, c2.0, to.0), blend(from.1, c1.1, c2.1, to.1)));
+        }
+        self.control = c2;
+    }
+
+    fn quadratic(&mut self, c: (f64, f64), to: (f64, f64)) {
+        let from = self.at;
+        for i in 1..=CURVE_LINES {
+            let t = i as f64 / CURVE_LINES as f64;
+            let s = 1.0 - t;
+            let blend = |a: f64, b: f64, c: f64| s * s * a + 2.0 * s * t * b + t * t * c;
+            self.line_to((blend(from.0, c.0, to.0), blend(from.1, c.1, to.1)));
+        }
+        self.control = c;
+    }
+
+    // Turns the SVG way of writing an arc, by where it ends, into a centre
+    // and angles to step through. See "Elliptical arc implementation notes"
+    // in the SVG spec.
+    fn arc(&mut self, radii: (f64, f64), rotation: f64, large: bool, sweep: bool, to: (f64, f64)) {
+        let from = self.at;
+        let (mut rx, mut ry) = (radii.0.abs(), radii.1.abs());
+        if from == to {
+            return;
+        }
+        if rx == 0.0 || ry == 0.0 {
+            return self.line_to(to);
+        }
+        let (sin, cos) = rotation.to_radians().sin_cos();
+        let (dx, dy) = ((from.0 - to.0) / 2.0, (from.1 - to.1) / 2.0);
+        let (x1, y1) = (cos * dx + sin * dy, cos * dy - sin * dx);
+        // radii too small to reach are scaled up until they just do
+        let reach = (x1 / rx).powi(2) + (y1 / ry).powi(2);
+        if reach > 1.0 {
+            rx *= reach.sqrt();
+            ry *= reach.sqrt();
+        }
+        let spare = (rx * ry).powi(2) - (rx * y1).powi(2) - (ry * x1).powi(2);
+        let share = (rx * y1).powi(2) + (ry * x1).powi(2);
+        let sign = if large == sweep { -1.0 } else { 1.0 };
+        let coef = sign * (spare / share).max(0.0).sqrt();
+        let (cx1, cy1) = (coef * rx * y1 / ry, -coef * ry * x1 / rx);
+        let centre = (
+            cos * cx1 - sin * cy1 + (from.0 + to.0) / 2.0,
+            sin * cx1 + cos * cy1 + (from.1 + to.1) / 2.0,
+        );
+        let angle = |x: f64, y: f64| y.atan2(x);
+        let start = angle((x1 - cx1) / rx, (y1 - cy1) / ry);
+        let mut turn = (angle((-x1 - cx1) / rx, (-y1 - cy1) / ry) - start).rem_euclid(TAU);
+        if !sweep && turn > 0.0 {
+            turn -= TAU;
+        }
+        let lines = (turn.abs() / ARC_TURN).ceil().max(1.0) as usize;
+        for i in 1..=lines {
+            let (s, c) = (start + turn * i as f64 / lines as f64).sin_cos();
+            let (x, y) = (rx * c, ry * s);
+            self.line_to((cos * x - sin * y + centre.0, sin * x + cos * y + centre.1));
+        }
+        // land exactly where asked
+        if let Some(end) = self.strokes.last_mut().and_then(|stroke| stroke.last_mut()) {
+            *end = to;
+        }
+        self.at = to;
+        self.control = to;
+    }
+
+    fn close(&mut self) {
+        if !self.closed && self.at != self.start {
+            self.line_to(self.start);
+        }
+        self.at = self.start;
+        self.control = self.start;
+        self.closed = true;
+    }
+}
+
+// An SVG path in one colour, placed on a canvas.
+#[derive(Debug, Clone)]
+pub struct Drawing<'a> {
+    pub path: &'a SvgPath,
+    pub transform: Transform,
+    pub color: Color,
+}
+
+impl Shape for Drawing<'_> {
+    fn draw(&self, painter: &mut Painter<'_, '_>) {
+        for stroke in &self.path.strokes {
+            let mut dots = Vec::new();
+            if let [point] = stroke[..] {
+                let (x, y) = self.transform.apply(point);
+                dots.push(painter.get_point(x, y));
+            }
+            // each line on its own, so no corner is cut
+            for line in stroke.windows(2) {
+                let (from, to) = (self.transform.apply(line[0]), self.transform.apply(line[1]));
+                let points = trace(painter, |t| {
+                    (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t)
+                });
+                dots.extend(points.into_iter().map(|(_, _, dot)| dot));
+            }
+            for (x, y) in dots.into_iter().flatten() {
+                painter.paint(x, y, self.color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(d: &str) -> SvgPath {
+        d.parse().unwrap_or_else(|err| panic!("{d:?}: {err}"))
+    }
+
+    fn same(a: &str, b: &str) {
+        assert_eq!(path(a), path(b), "{a:?} vs {b:?}");
+    }
+
+    #[test]
+    fn numbers_run_together() {
+        same("M1-2.5.5.5", "M 1 -2.5 L 0.5 0.5");
+        same("M0,0L1e1,0l-.5-5", "M 0 0 L 10 0 L 9.5 -5");
+    }
+
+    #[test]
+    fn relative_commands() {
+        same("M10 10 l5 0 0 5 h-5 v-5", "M10 10 L15 10 L15 15 L10 15 L10 10");
+        same("M10 10 c0 5 5 5 5 0", "M10 10 C10 15 15 15 15 10");
+        same("M10 10 q2.5 5 5 0", "M10 10 Q12.5 15 15 10");
+        same("M10 10 a5 5 0 0 1 10 0", "M10 10 A5 5 0 0 1 20 10");
+    }
+
+    #[test]
+    fn implicit_lineto_after_move() {
+        same("M0 0 10 0 10 10", "M0 0 L10 0 L10 10");
+        same("m1 1 2 0 0 2", "M1 1 L3 1 L3 3");
+        assert_eq!(path("M0 0 10 0 10 10").strokes().len(), 1);
+    }
+
+    #[test]
+    fn move_after_close() {
+        // a z takes the pen back to where the stroke started
+        same("M0 0 L10 0 L10 10 z m5 5 l1 1", "M0 0 L10 0 L10 10 Z M5 5 L6 6");
+        // and drawing on without a move starts a new stroke from there
+        let closed = path("M0 0 L10 0 L10 10 Z L0 10");
+        assert_eq!(closed.strokes().len(), 2);
+        assert_eq!(closed.strokes()[0].first(), closed.strokes()[0].last());
+        assert_eq!(closed.strokes()[1].first(), closed.strokes()[0].first());
+    }
+
+    #[test]
+    fn smooth_curves_reflect() {
+        same("M0 0 C0 10 10 10 10 0 S20 -10 20 0", "M0 0 C0 10 10 10 10 0 C10 -10 20 -10 20 0");
+        same("M0 0 C0 10 10 10 10 0 s10 -10 10 0", "M0 0 C0 10 10 10 10 0 C10 -10 20 -10 20 0");
+        same("M0 0 Q5 10 10 0 T20 0", "M0 0 Q5 10 10 0 Q15 -10 20 0");
+        same("M0 0 Q5 10 10 0 T20 0 T30 0", "M0 0 Q5 10 10 0 Q15 -10 20 0 Q25 10 30 0");
+        // with no curve before, the pen is the control point
+        same("M0 0 L10 0 S20 10 20 0", "M0 0 L10 0 C10 0 20 10 20 0");
+        same("M0 0 L10 0 T20 0", "M0 0 L10 0 Q10 0 20 0");
+    }
+
+    #[test]
+    fn arc_flags_without_separators() {
+        same("M0 0 A5 5 0 1110 0", "M0 0 A5 5 0 1 1 10 0");
+        same("M0 0 a5,5,0,0110,0", "M0 0 A5 5 0 0 1 10 0");
+        // the flags pick different arcs
+        assert_ne!(path("M0 0 A5 5 0 0 1 10 0"), path("M0 0 A5 5 0 0 0 10 0"));
+    }
+
+    #[test]
+    fn errors_say_where() {
+        let err = |d: &str| d.parse::<SvgPath>().unwrap_err();
+        assert_eq!(err("M0 0 L10 x"), "expected a number at 9");
+        assert_eq!(err("M0 0 A5 5 0 2 1 1 1"), "expected 0 or 1 at 12");
+        assert_eq!(err("M0 0 L1 1 Z 5 5"), "expected a command at 12");
+        assert_eq!(err("5 5"), "expected a command at 0");
+        assert_eq!(err("L0 0"), "a path starts with M");
+        assert_eq!(err("M0 0 X1 1"), "unknown path command 'X'");
+        assert_eq!(err(""), "the path doesn't draw anything");
+    }
+
+    #[test]
+    fn fitted_y_up() {
+        let fitted = path("M0 0 L0 10");
+        assert_eq!(fitted.strokes(), [vec![(0.0, 1.0), (0.0, -1.0)]]);
+    }
+}
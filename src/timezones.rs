@@ -0,0 +1,48 @@
+use chrono::{DateTime, Offset, TimeZone, Timelike};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+// Both partners' cities for long distance couples, by IANA zone name.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LongDistance {
+    pub me: City,
+    pub you: City,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct City {
+    pub label: String,
+    pub zone: Tz,
+}
+
+impl City {
+    pub fn at<T: TimeZone>(&self, time: &DateTime<T>) -> DateTime<Tz> {
+        time.with_timezone(&self.zone)
+    }
+
+    pub fn is_daytime<T: TimeZone>(&self, time: &DateTime<T>) -> bool {
+        (6..18).contains(&self.at(time).hour())
+    }
+}
+
+impl LongDistance {
+    // "London is 6h ahead of Chicago", worked out at `time` so daylight
+    // saving is taken into account.
+    pub fn difference<T: TimeZone>(&self, time: &DateTime<T>) -> String {
+        let mine = self.me.at(time).offset().fix().local_minus_utc();
+        let yours = self.you.at(time).offset().fix().local_minus_utc();
+        let diff = yours - mine;
+        if diff == 0 {
+            return format!("{} and {} share a clock", self.you.label, self.me.label);
+        }
+        let minutes = diff.abs() / 60;
+        let amount = match minutes % 60 {
+            0 => format!("{}h", minutes / 60),
+            m => format!("{}h{:02}m", minutes / 60, m),
+        };
+        let direction = if diff > 0 { "ahead of" } else { "behind" };
+        format!("{} is {} {} {}", self.you.label, amount, direction, self.me.label)
+    }
+}
@@ -0,0 +1,163 @@
+use ratatui::{
+    buffer::{Buffer, Cell},
+    layout::{Position, Rect},
+};
+use serde::Deserialize;
+
+use crate::color::{mix, noise, rgb, BLACK, WHITE};
+
+// How one screen gives way to the next, from the card's [transitions] table.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Transitions {
+    pub effect: Effect,
+    // how long it takes, 0 to switch straight away
+    pub ticks: u16,
+}
+
+impl Default for Transitions {
+    fn default() -> Self {
+        Self {
+            effect: Effect::Fade,
+            ticks: 8,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Effect {
+    None,
+    // the old screen dims away and the new one brightens in
+    #[default]
+    Fade,
+    // the new screen slides in from the left
+    Wipe,
+    // cell by cell, in a scattered order
+    Dissolve,
+    // a heart opening up from the middle
+    Iris,
+}
+
+// A scene change in progress, blending the last frame of the old screen into
+// whatever the new one draws.
+#[derive(Debug, Clone)]
+pub struct Transition {
+    effect: Effect,
+    from: Buffer,
+    tick: u16,
+    ticks: u16,
+}
+
+impl Transition {
+    pub fn new(effect: Effect, from: Buffer, ticks: u16) -> Self {
+        Self {
+            effect,
+            from,
+            tick: 0,
+            ticks,
+        }
+    }
+
+    // False once it's over.
+    pub fn step(&mut self) -> bool {
+        self.tick = self.tick.saturating_add(1);
+        self.tick < self.ticks
+    }
+
+    // 0 shows only the old screen, 1 only the new one. The first frame
+    // already moves a little.
+    pub fn progress(&self) -> f64 {
+        // in f64, as ticks can be all of u16
+        (f64::from(self.tick) + 1.0) / (f64::from(self.ticks) + 1.0)
+    }
+
+    // `buf` already holds the new screen, this mixes the old one back in.
+    pub fn blend(&self, area: Rect, buf: &mut Buffer) {
+        let progress = self.progress();
+        let reach = iris_reach(area);
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let position = Position::new(x, y);
+                let Some(old) = self.from.cell(position) else {
+                    continue;
+                };
+                let new = &mut buf[position];
+                match self.effect {
+                    Effect::None => {}
+                    Effect::Fade => fade(old, new, progress),
+                    Effect::Wipe => {
+                        if f64::from(x - area.x) >= progress * f64::from(area.width) {
+                            *new = old.clone();
+                        }
+                    }
+                    Effect::Dissolve => {
+                        if noise(x, y) >= progress {
+                            *new = old.clone();
+                        }
+                    }
+                    Effect::Iris => {
+                        if !in_heart(area, x, y, progress * reach) {
+                            *new = old.clone();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// The old cell fades into its background for the first half, then the new
+// one comes up out of its own. The backgrounds blend the whole way.
+fn fade(old: &Cell, new: &mut Cell, progress: f64) {
+    let bg = mix(rgb(old.bg, BLACK), rgb(new.bg, BLACK), progress);
+    if progress < 0.5 {
+        let fg = mix(rgb(old.fg, WHITE), rgb(old.bg, BLACK), progress * 2.0);
+        *new = old.clone();
+        new.set_fg(fg);
+    } else {
+        let fg = mix(rgb(new.bg, BLACK), rgb(new.fg, WHITE), progress * 2.0 - 1.0);
+        new.set_fg(fg);
+    }
+    new.set_bg(bg);
+}
+
+// How big the iris heart has to get before it covers every corner of `area`.
+fn iris_reach(area: Rect) -> f64 {
+    let half_width = f64::from(area.width) / 2.0;
+    // cells are about twice as tall as they are wide
+    let half_height = f64::from(area.height);
+    1.8 * half_width.hypot(half_height)
+}
+
+// Whether the cell is inside a heart of `size` cells across the middle of
+// `area`, using (x² + y² - 1)³ = x²y³.
+fn in_heart(area: Rect, x: u16, y: u16, size: f64) -> bool {
+    if size <= 0.0 {
+        return false;
+    }
+    let dx = (f64::from(x) + 0.5 - f64::from(area.x) - f64::from(area.width) / 2.0) / size;
+    let dy = (f64::from(area.y) + f64::from(area.height) / 2.0 - f64::from(y) - 0.5) * 2.0 / size;
+    (dx * dx + dy * dy - 1.0).powi(3) - dx * dx * dy.powi(3) <= 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_transition_runs_to_the_end() {
+        let from = Buffer::empty(Rect::new(0, 0, 1, 1));
+        let mut transition = Transition::new(Effect::Fade, from, u16::MAX);
+        let mut last = 0.0;
+        while transition.step() {
+            let progress = transition.progress();
+            assert!(progress > last && progress < 1.0);
+            last = progress;
+        }
+        assert_eq!(transition.progress(), 1.0);
+        // stepping on past the end stays there
+        assert!(!transition.step());
+        assert_eq!(transition.progress(), 1.0);
+    }
+}
@@ -4,17 +4,39 @@ use ratatui::{
     style::{Color, Modifier, Style, Stylize},
     text::{Line, Span, Text},
     widgets::{
-        block::Padding,
-        canvas::{Canvas, Painter},
-        Block, Borders, Clear, List, ListItem, Paragraph, Widget, Wrap,
+        block::Padding, canvas::Canvas, Block, Borders, List, ListItem, Paragraph,
+        StatefulWidget, Widget, Wrap,
     },
     Frame,
 };
 
 use crate::app::{App, CurrentScreen, IntroScreenOptions};
-use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use crate::advent::SurpriseKind;
+use crate::card::{local_time, EventStatus};
+use crate::timezones::{City, LongDistance};
+use crate::dialog::{Dialog, Size};
+use crate::heart::{heartbeat, Fill, Heart};
+use crate::input::TextInputWidget;
+use crate::keymap::Action;
+use crate::unlock::Unlock;
+use chrono::{DateTime, Duration, Local, Timelike};
 use std::rc::Rc;
 
+fn format_countdown(remaining: Duration) -> String {
+    let hours = remaining.num_hours();
+    let minutes = remaining.num_minutes() % 60;
+    let seconds = remaining.num_seconds() % 60;
+    format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    match elapsed.num_days() {
+        0 => format_countdown(elapsed),
+        1 => format!("1 day {}", format_countdown(elapsed - Duration::days(1))),
+        days => format!("{} days {}", days, format_countdown(elapsed - Duration::days(days))),
+    }
+}
+
 fn center(area: Rect, horizontal: Constraint, vertical: Constraint) -> Rect {
     let [area] = Layout::horizontal([horizontal])
         .flex(Flex::Center)
@@ -22,38 +44,83 @@ fn center(area: Rect, horizontal: Constraint, vertical: Constraint) -> Rect {
     let [area] = Layout::vertical([vertical]).flex(Flex::Center).areas(area);
     area
 }
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-    // Cut the given rectangle into three vertical pieces
-    let popup_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
-        ])
-        .split(r);
-
-    // Then cut the middle vertical piece into three width-wise pieces
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
-        ])
-        .split(popup_layout[1])[1] // Return the middle chunk
+
+// Below this there's no room for the popups, so the card asks for more.
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 18;
+
+// How the screens lay themselves out for the terminal they're given.
+// Compact is the classic 80x24 and anything smaller, wide is a big
+// maximised window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Breakpoint {
+    Compact,
+    Normal,
+    Wide,
 }
 
-pub fn ui(frame: &mut Frame, app: &mut App) {
-    // setting up base layout and boxes
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .margin(2)
-        .constraints([Constraint::Length(5), Constraint::Min(10)].as_ref())
-        .split(frame.area());
+impl Breakpoint {
+    fn of(area: Rect) -> Self {
+        if area.width < 100 || area.height < 30 {
+            Breakpoint::Compact
+        } else if area.width >= 160 && area.height >= 40 {
+            Breakpoint::Wide
+        } else {
+            Breakpoint::Normal
+        }
+    }
+
+    // Width for a popup measured against the whole screen, given as what
+    // looks right on a normal terminal.
+    fn width(self, percent: u16) -> Size {
+        match self {
+            Breakpoint::Compact => Size::Percentage(percent.max(90)),
+            Breakpoint::Normal => Size::Percentage(percent),
+            Breakpoint::Wide => Size::Percentage(percent * 3 / 4),
+        }
+    }
+
+    // Popups inside one of the main chunks just hug their text when space
+    // is tight, a fraction of a small chunk is too small to read.
+    fn fit(self, size: Size) -> Size {
+        match self {
+            Breakpoint::Compact => Size::FitContent,
+            _ => size,
+        }
+    }
+}
+
+fn too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+fn render_too_small(area: Rect, buf: &mut Buffer) {
+    Block::new().on_black().render(area, buf);
+    let message = Paragraph::new(Text::from(vec![
+        Line::from("Please make your terminal a little bigger <3".magenta().bold()),
+        Line::from(""),
+        Line::from(
+            format!(
+                "it's {}x{}, the card needs {}x{}",
+                area.width, area.height, MIN_WIDTH, MIN_HEIGHT
+            )
+            .white(),
+        ),
+    ]))
+    .wrap(Wrap { trim: true })
+    .centered();
+    message.render(center(area, Constraint::Percentage(100), Constraint::Length(5)), buf);
+}
+
+pub fn ui(area: Rect, buf: &mut Buffer, app: &mut App) {
+    if too_small(area) {
+        render_too_small(area, buf);
+        return;
+    }
+    let breakpoint = Breakpoint::of(area);
 
     let title_text = "As you might know, I have a question I've been meaning to ask you.";
-    let title = Paragraph::new(Text::from(vec![
+    let mut title_lines = vec![
         match app.current_screen {
             CurrentScreen::IntroScreen => Line::from(title_text).white(),
             _ => Line::from(""),
@@ -62,102 +129,182 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
             CurrentScreen::WillYouBeMyValentine => {
                 Line::from("I LOOOOOOOOOOOOOOOOOOOOOOOOOOOOOOOOOOOOOOOOOVE YOUUUU")
             }
-            CurrentScreen::IntroScreen => Line::from("Press any key to continue".magenta()),
+            CurrentScreen::IntroScreen if app.can_go_back() => Line::from(
+                format!(
+                    "Press {} to continue, {} to go back",
+                    app.card.keys.hint(Action::Advance),
+                    app.card.keys.hint(Action::Back)
+                )
+                .magenta(),
+            ),
+            CurrentScreen::IntroScreen => Line::from(
+                format!("Press {} to continue", app.card.keys.hint(Action::Advance)).magenta(),
+            ),
+            CurrentScreen::Gate => Line::from("Just making sure it's you...".magenta()),
+            CurrentScreen::Advent => Line::from("One little surprise every day until our date"),
             CurrentScreen::YesValentine => Line::from("Please say yes"),
             CurrentScreen::NotValentine => Line::from("I'm gonna kill you"),
+            CurrentScreen::Rsvp => Line::from("Almost there..."),
+            CurrentScreen::DateChoice => Line::from("Your pick, pretty lady"),
             CurrentScreen::DateInfo => Line::from("Bag secured!!! B)"),
+            CurrentScreen::Itinerary => Line::from("Our big day, step by step"),
+            CurrentScreen::Anniversary => Line::from("Every day with you counts"),
+            CurrentScreen::Letters => Line::from("Little notes, just for you"),
+            CurrentScreen::Celebration => Line::from("IT'S TIME!!!"),
+            CurrentScreen::Custom(_) => Line::from(""),
         },
-    ]))
-    .style(Style::default().fg(Color::White))
-    .on_black()
-    .centered()
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title("To My Valentine...")
-            .title_style(Style::new().magenta())
-            .borders(Borders::ALL)
-            .border_style(Style::new().magenta()),
-    );
-    frame.render_widget(title, chunks[0]);
-
-    let main_chunks = Layout::default()
+    ];
+
+    // setting up base layout and boxes. Compact terminals can't spare the
+    // margin or the blank header line, and the header grows when its text
+    // has to wrap
+    let (margin, header_height) = match breakpoint {
+        Breakpoint::Compact => {
+            title_lines.retain(|line| line.width() > 0);
+            let width = usize::from(area.width.saturating_sub(2)).max(1);
+            let rows: usize = title_lines
+                .iter()
+                .map(|line| line.width().div_ceil(width).max(1))
+                .sum();
+            (0, (rows as u16 + 2).clamp(3, 5))
+        }
+        _ => (2, 5),
+    };
+    let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Max(50), Constraint::Max(50)].as_ref())
-        .split(chunks[1]);
+        .margin(margin)
+        .constraints([Constraint::Length(header_height), Constraint::Min(10)].as_ref())
+        .split(area);
 
-    match app.current_screen {
-        CurrentScreen::WillYouBeMyValentine => render_valentine_screen(frame, app, main_chunks),
-        CurrentScreen::IntroScreen => render_intro_screens(frame, app, main_chunks),
-        CurrentScreen::YesValentine => render_yes_screen(frame, app, main_chunks),
-        CurrentScreen::NotValentine => render_no_screen(frame, app, main_chunks),
-        CurrentScreen::DateInfo => render_dateinfo_screen(frame, app, main_chunks),
+    let title = Paragraph::new(Text::from(title_lines))
+        .style(Style::default().fg(Color::White))
+        .on_black()
+        .wrap(Wrap { trim: true })
+        .centered()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(app.title)
+                .title_style(Style::new().magenta())
+                .title_bottom(header_hint(app).right_aligned())
+                .borders(Borders::ALL)
+                .border_style(Style::new().magenta()),
+        );
+    title.render(chunks[0], buf);
 
-        _ => {}
+    // side by side when there's width to spare
+    let main_chunks = match breakpoint {
+        Breakpoint::Wide => Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(chunks[1]),
+        // the popups need the room more than the heart does
+        Breakpoint::Compact => Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+            .split(chunks[1]),
+        Breakpoint::Normal => Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Max(50), Constraint::Max(50)].as_ref())
+            .split(chunks[1]),
+    };
+
+    // every screen bounces the heart around the second chunk, whatever
+    // size it is this frame
+    app.playground = main_chunks[1];
+
+    if let Some(unlock_at) = app.card.locked_until(&app.current_screen, app.clock.now()) {
+        render_locked_screen(buf, app, main_chunks, unlock_at);
+    } else {
+        app.render_scene(area, buf, main_chunks);
+    }
+    if app.show_help {
+        render_help(area, buf, app);
     }
 }
 
-fn render_valentine_screen(frame: &mut Frame, app: &mut App, main_chunks: Rc<[Rect]>) {
-    let my_string = match app.offset {
-        0 => "My Valentine My Lovely Lady My Love <3 ".repeat(500),
-        1 => "My Lovely Lady My Love <3 ".repeat(500),
-        2 => "My Love <3 ".repeat(500),
-        3 => " <3 ".repeat(900),
-        _ => "My Valentine My Lovely Lady My Love <3 ".repeat(500),
-    };
-    let valentines_background = Paragraph::new(Text::from(my_string))
-        .wrap(Wrap { trim: false })
-        .style(
-            Style::default()
-                .fg(Color::Magenta)
-                .bg(Color::Black)
-                .add_modifier(Modifier::ITALIC | Modifier::BOLD),
-        )
-        .centered()
-        .block(Block::default().borders(Borders::ALL).title(""))
-        .alignment(Alignment::Center);
-    frame.render_widget(valentines_background, main_chunks[0]);
+fn header_hint(app: &App) -> Line<'static> {
+    let keys = &app.card.keys;
+    match app.current_screen {
+        // '?' is just a character while typing
+        CurrentScreen::Gate | CurrentScreen::Rsvp => Line::from(""),
+        _ if app.paused => Line::from(
+            format!(" paused, {} to resume ", keys.hint(Action::Pause)).magenta(),
+        ),
+        _ => Line::from(format!(" {} for help ", keys.hint(Action::Help)).dark_gray()),
+    }
+}
 
-    let area = frame.area();
-    let popup_area = Rect {
-        x: area.width / 3,
-        y: area.height / 5,
-        width: area.width / 4,
-        height: area.height / 3,
-    };
+fn render_help(area: Rect, buf: &mut Buffer, app: &App) {
+    let entries = app.help_entries();
+    let key_width = entries
+        .iter()
+        .map(|(keys, _)| keys.chars().count())
+        .max()
+        .unwrap_or(0);
+    let lines: Vec<Line> = entries
+        .into_iter()
+        .map(|(keys, what)| {
+            Line::from(vec![
+                format!("{:>width$}", keys, width = key_width).magenta().bold(),
+                "  ".into(),
+                what.white(),
+            ])
+        })
+        .collect();
+    let help = Dialog::new("Keys")
+        .body(lines)
+        .alignment(Alignment::Left)
+        .hint("any key to close")
+        .title_style(Style::new().white().bold())
+        .border_style(Style::new().magenta())
+        .style(Style::new().on_black())
+        .padding(Padding::uniform(1));
+    help.render(area, buf);
+}
 
-    frame.render_widget(Clear, popup_area);
-    let wybmv = Paragraph::new(Text::from(vec![
-        Line::from("Will ".magenta().bold()),
-        Line::from("You ".magenta()).bold(),
-        Line::from("Be ".magenta()).bold(),
-        Line::from("My ".magenta()).bold(),
-        Line::from(
-            "Valentine?"
-                .magenta()
-                .add_modifier(Modifier::BOLD | Modifier::ITALIC),
-        ),
-        Line::from(""),
-        Line::from("please type y/n"),
-    ]))
-    .wrap(Wrap { trim: false })
-    .alignment(Alignment::Center)
-    .style(Style::new().magenta().on_black())
-    .centered()
-    .block(
-        Block::new()
-            .title("To my pretty lady:")
-            .title_style(Style::new().white().bold())
-            .borders(Borders::ALL)
-            .padding(Padding::new(0, 0, popup_area.height / 4, 0))
-            .border_style(Style::new().white()),
-    );
-    frame.render_widget(wybmv, popup_area);
+// The animated box behind a screen's popups, whichever one the card picked
+// for this screen.
+pub fn render_background(buf: &mut Buffer, app: &mut App, area: Rect) {
+    let block = Block::bordered().style(Style::new().magenta().on_black());
+    let inner = block.inner(area);
+    block.render(area, buf);
+    let kind = app.card.backgrounds.for_screen(&app.current_screen);
+    app.background_cache.render(kind, inner, buf, app.tick_count);
+}
+pub fn render_valentine_screen(
+    area: Rect,
+    buf: &mut Buffer,
+    app: &mut App,
+    main_chunks: Rc<[Rect]>,
+) {
+    let breakpoint = Breakpoint::of(area);
+    render_background(buf, app, main_chunks[0]);
 
-    app.playground = main_chunks[1];
-    frame.render_widget(app.pong_canvas(app.playground), app.playground);
+    let wybmv = Dialog::new("To my pretty lady:")
+        .body(vec![
+            Line::from("Will ".magenta().bold()),
+            Line::from("You ".magenta()).bold(),
+            Line::from("Be ".magenta()).bold(),
+            Line::from("My ".magenta()).bold(),
+            Line::from(
+                "Valentine?"
+                    .magenta()
+                    .add_modifier(Modifier::BOLD | Modifier::ITALIC),
+            ),
+        ])
+        .button(app.card.keys.hint(Action::Yes), "Yes")
+        .button(app.card.keys.hint(Action::No), "No")
+        .size(breakpoint.fit(Size::Percentage(30)), breakpoint.fit(Size::Percentage(40)))
+        .middle()
+        .style(Style::new().magenta().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style(Style::new().white());
+    wybmv.render(main_chunks[0], buf);
+
+    app.pong_canvas(app.playground).render(app.playground, buf);
 }
-fn render_intro_screens(frame: &mut Frame, app: &App, main_chunks: Rc<[Rect]>) {
+pub fn render_intro_screens(buf: &mut Buffer, app: &App, main_chunks: Rc<[Rect]>) {
     match app.intro_screen {
         IntroScreenOptions::Screen1 => {}
         IntroScreenOptions::Screen2 => {
@@ -172,12 +319,12 @@ fn render_intro_screens(frame: &mut Frame, app: &App, main_chunks: Rc<[Rect]>) {
             .block(
                 Block::new()
                     .borders(Borders::ALL)
-                    .padding(Padding::new(0, 0, frame.area().height / 4, 0))
+                    .padding(Padding::new(0, 0, middle_padding(main_chunks[0]), 0))
                     .title_style(Style::new().magenta())
                     .borders(Borders::ALL)
                     .border_style(Style::new().magenta()),
             );
-            frame.render_widget(next_text, main_chunks[0]);
+            next_text.render(main_chunks[0], buf);
         }
         IntroScreenOptions::Screen3 => {
             let next_text2 = Paragraph::new(Text::from(vec![
@@ -191,12 +338,12 @@ fn render_intro_screens(frame: &mut Frame, app: &App, main_chunks: Rc<[Rect]>) {
             .block(
                 Block::new()
                     .borders(Borders::ALL)
-                    .padding(Padding::new(0, 0, frame.area().height / 4, 0))
+                    .padding(Padding::new(0, 0, middle_padding(main_chunks[0]), 0))
                     .title_style(Style::new().magenta())
                     .borders(Borders::ALL)
                     .border_style(Style::new().magenta()),
             );
-            frame.render_widget(next_text2, main_chunks[0]);
+            next_text2.render(main_chunks[0], buf);
 
             let next_text = Paragraph::new(Text::from(vec![
                 Line::from("You've waited long enough my pretty lady".white()),
@@ -209,160 +356,205 @@ fn render_intro_screens(frame: &mut Frame, app: &App, main_chunks: Rc<[Rect]>) {
             .block(
                 Block::new()
                     .borders(Borders::ALL)
-                    .padding(Padding::new(0, 0, frame.area().height / 4, 0))
+                    .padding(Padding::new(0, 0, middle_padding(main_chunks[1]), 0))
                     .title_style(Style::new().magenta())
                     .borders(Borders::ALL)
                     .border_style(Style::new().magenta()),
             );
 
-            frame.render_widget(next_text, main_chunks[1])
+            next_text.render(main_chunks[1], buf)
         }
     }
 }
-fn render_yes_screen(frame: &mut Frame, app: &App, main_chunks: Rc<[Rect]>) {
-    let my_string = match app.offset {
-        0 => "My Valentine My Lovely Lady My Love <3 ".repeat(300),
-        1 => "My Lovely Lady My Love <3 ".repeat(300),
-        2 => "My Love <3 ".repeat(300),
-        3 => " <3 ".repeat(500),
-        _ => "My Valentine My Lovely Lady My Love <3 ".repeat(300),
-    };
-    let valentines_background = Paragraph::new(Text::from(my_string))
-        .wrap(Wrap { trim: false })
-        .style(
-            Style::default()
-                .fg(Color::Magenta)
-                .bg(Color::Black)
-                .add_modifier(Modifier::ITALIC | Modifier::BOLD),
-        )
-        .centered()
-        .block(Block::default().borders(Borders::ALL).title(""))
-        .alignment(Alignment::Center);
-    frame.render_widget(valentines_background, main_chunks[0]);
+// Top padding that puts the two lines of an intro box in the middle of it.
+fn middle_padding(area: Rect) -> u16 {
+    area.height.saturating_sub(4) / 2
+}
+pub fn render_yes_screen(area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+    let breakpoint = Breakpoint::of(area);
+    render_background(buf, app, main_chunks[0]);
 
-    frame.render_widget(app.pong_canvas(app.playground), app.playground);
+    app.pong_canvas(app.playground).render(app.playground, buf);
 
-    let area = frame.area();
-    let popup_area = Rect {
-        x: area.width / 3,
-        y: area.height / 5,
-        width: area.width / 4,
-        height: area.height / 3,
-    };
-    frame.render_widget(Clear, popup_area);
-    let wybmv = Paragraph::new(Text::from(vec![
-        Line::from("How amazing! ".green()),
-        Line::from("I must be the luckiest guy ever! ".green()),
-        Line::from(""),
-        Line::from("Would you like to know our Valentines plans? ".green()),
-        Line::from(""),
-        Line::from("please enter y/n ".green()),
-    ]))
-    .wrap(Wrap { trim: false })
-    .alignment(Alignment::Center)
-    .centered()
-    .on_black()
-    .block(
-        Block::new()
-            .title("Lucky meeeee")
-            .title_style(Style::new().white().bold())
-            .borders(Borders::ALL)
-            .padding(Padding::new(0, 0, popup_area.height / 4, 0))
-            .border_style({
-                match app.offset % 2 {
-                    0 => Style::new().gray(),
-                    1 => Style::new().green(),
-                    _ => Style::new().gray(),
-                }
-            }),
-    );
-    frame.render_widget(wybmv, popup_area);
+    let wybmv = Dialog::new("Lucky meeeee")
+        .body(vec![
+            Line::from("How amazing! ".green()),
+            Line::from("I must be the luckiest guy ever! ".green()),
+            Line::from(""),
+            Line::from("Would you like to know our Valentines plans? ".green()),
+        ])
+        .button(app.card.keys.hint(Action::Yes), "Show me!".green())
+        .button(app.card.keys.hint(Action::No), "Wait".green())
+        .size(breakpoint.fit(Size::Percentage(40)), breakpoint.fit(Size::Percentage(40)))
+        .middle()
+        .style(Style::new().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style({
+            match app.offset % 2 {
+                0 => Style::new().gray(),
+                1 => Style::new().green(),
+                _ => Style::new().gray(),
+            }
+        });
+    wybmv.render(main_chunks[0], buf);
 }
-fn render_no_screen(frame: &mut Frame, app: &App, main_chunks: Rc<[Rect]>) {
-    let my_string = match app.offset {
-        0 => "My Valentine My Lovely Lady My Love <3 ".repeat(300),
-        1 => "My Lovely Lady My Love <3 ".repeat(300),
-        2 => "My Love <3 ".repeat(300),
-        3 => " <3 ".repeat(500),
-        _ => "My Valentine My Lovely Lady My Love <3 ".repeat(300),
-    };
-    let valentines_background = Paragraph::new(Text::from(my_string))
-        .wrap(Wrap { trim: false })
-        .style(
-            Style::default()
-                .fg(Color::Magenta)
-                .bg(Color::Black)
-                .add_modifier(Modifier::ITALIC | Modifier::BOLD),
-        )
-        .centered()
-        .block(Block::default().borders(Borders::ALL).title(""))
-        .alignment(Alignment::Center);
-    frame.render_widget(valentines_background, main_chunks[0]);
+pub fn render_no_screen(area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+    let breakpoint = Breakpoint::of(area);
+    render_background(buf, app, main_chunks[0]);
 
-    frame.render_widget(app.pong_canvas(app.playground), app.playground);
-    let area = frame.area();
-
-    let popup_area = Rect {
-        x: area.width / 3,
-        y: area.height / 5,
-        width: area.width / 4,
-        height: area.height / 3,
-    };
-    frame.render_widget(Clear, popup_area);
-    let wybmv = Paragraph::new(Text::from(vec![
+    app.pong_canvas(app.playground).render(app.playground, buf);
+    let mut lines = vec![
         Line::from("THAT ".red()),
         Line::from("WAS ".red()),
         Line::from("THE ".red()),
         Line::from("WRONG ".red()),
         Line::from("ANSWER!!!!".red().add_modifier(Modifier::BOLD)),
+    ];
+    if app.saved.no_count > 1 {
+        lines.push(Line::from(
+            format!("(that's {} times now)", app.saved.no_count).red(),
+        ));
+    }
+    let wybmv = Dialog::new("FUCK YOUUUUU")
+        .body(lines)
+        .button(app.card.keys.hint(Action::Advance), "try again".red())
+        .size(breakpoint.fit(Size::Percentage(30)), breakpoint.fit(Size::Percentage(40)))
+        .middle()
+        .style(Style::new().red().on_black())
+        .title_style(Style::new().red().bold())
+        .border_style({
+            match app.offset % 2 {
+                0 => Style::new().gray(),
+                1 => Style::new().red(),
+                _ => Style::new().gray(),
+            }
+        });
+    wybmv.render(main_chunks[0], buf);
+}
+pub fn render_rsvp_screen(area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+    let breakpoint = Breakpoint::of(area);
+    render_background(buf, app, main_chunks[0]);
+
+    app.pong_canvas(app.playground).render(app.playground, buf);
+
+    let popup = Dialog::new("RSVP")
+        .size(breakpoint.width(50), Size::Fixed(12))
+        .padding(Padding::ZERO)
+        .style(Style::new().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style(Style::new().green());
+    let inner = popup.inner(area);
+    popup.render(area, buf);
+
+    let [prompt_area, input_area, help_area] = Layout::vertical([
+        Constraint::Length(5),
+        Constraint::Length(3),
+        Constraint::Length(2),
+    ])
+    .areas(inner);
+    let prompt = Paragraph::new(Text::from(vec![
+        Line::from("Yay!!! One last thing before the plans...".green()),
         Line::from(""),
-        Line::from("press any key to continue").red(),
+        Line::from("Anything I should know? A note, a craving, a dietary preference...".white()),
     ]))
-    .wrap(Wrap { trim: false })
-    .alignment(Alignment::Center)
-    .centered()
-    .on_black()
-    .block(
-        Block::new()
-            .title("FUCK YOUUUUU")
-            .title_style(Style::new().red().bold())
-            .borders(Borders::ALL)
-            .padding(Padding::new(0, 0, popup_area.height / 4, 0))
-            .border_style({
-                match app.offset % 2 {
-                    0 => Style::new().gray(),
-                    1 => Style::new().red(),
-                    _ => Style::new().gray(),
-                }
-            }),
-    );
-    frame.render_widget(wybmv, popup_area);
+    .wrap(Wrap { trim: true })
+    .centered();
+    prompt.render(prompt_area, buf);
+
+    let input = TextInputWidget::new(&app.rsvp_input)
+        .style(Style::new().white())
+        .placeholder("type here...".dark_gray().italic())
+        .block(
+            Block::bordered()
+                .border_style(Style::new().magenta())
+                .padding(Padding::horizontal(1)),
+        );
+    StatefulWidget::render(input, input_area, buf, &mut app.rsvp_input_state);
+    if let Some(cursor) = app.rsvp_input_state.cursor {
+        app.cursor = Some(cursor);
+    }
+
+    let help = Paragraph::new(Line::from("enter to send, esc to skip".dark_gray())).centered();
+    help.render(help_area, buf);
 }
-fn render_dateinfo_screen(frame: &mut Frame, app: &App, main_chunks: Rc<[Rect]>) {
-    let my_string = match app.offset {
-        0 => "My Valentine My Lovely Lady My Love <3 ".repeat(300),
-        1 => "My Lovely Lady My Love <3 ".repeat(300),
-        2 => "My Love <3 ".repeat(300),
-        3 => " <3 ".repeat(500),
-        _ => "My Valentine My Lovely Lady My Love <3 ".repeat(300),
-    };
-    let valentines_background = Paragraph::new(Text::from(my_string))
-        .wrap(Wrap { trim: false })
-        .style(
-            Style::default()
-                .fg(Color::Magenta)
-                .bg(Color::Black)
-                .add_modifier(Modifier::ITALIC | Modifier::BOLD),
-        )
-        .centered()
-        .block(Block::default().borders(Borders::ALL).title(""))
-        .alignment(Alignment::Center);
-    frame.render_widget(valentines_background, main_chunks[0]);
+pub fn render_datechoice_screen(
+    area: Rect,
+    buf: &mut Buffer,
+    app: &mut App,
+    main_chunks: Rc<[Rect]>,
+) {
+    let breakpoint = Breakpoint::of(area);
+    render_background(buf, app, main_chunks[0]);
 
-    frame.render_widget(app.pong_canvas(app.playground), app.playground);
-    let area = frame.area();
-    let target_time = Local.with_ymd_and_hms(2025, 2, 15, 16, 0, 0).unwrap();
-    let now = Local::now();
+    app.pong_canvas(app.playground).render(app.playground, buf);
+
+    let popup = Dialog::new("Pick our date:")
+        .hint(format!(
+            "up/down or click, {} to pick",
+            app.card.keys.hint(Action::Advance)
+        ))
+        .size(breakpoint.width(70), Size::Fixed(16))
+        .padding(Padding::ZERO)
+        .style(Style::new().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style(Style::new().white());
+    let inner = popup.inner(area);
+    popup.render(area, buf);
+
+    let [list_area, details_area] =
+        Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .areas(inner);
+
+    let items: Vec<ListItem> = app
+        .card
+        .plans
+        .iter()
+        .map(|plan| {
+            ListItem::new(vec![
+                Line::from(plan.venue.clone().bold()),
+                Line::from(format!("  {}", plan.time.format("%a %-I:%M %p")).dark_gray()),
+            ])
+        })
+        .collect();
+    let list_block = Block::new()
+        .borders(Borders::RIGHT)
+        .border_style(Style::new().magenta());
+    app.plan_list_area = list_block.inner(list_area);
+    let list = List::new(items)
+        .block(list_block)
+        .style(Style::new().white())
+        .highlight_style(Style::new().black().on_magenta())
+        .highlight_symbol("<3 ");
+    StatefulWidget::render(list, list_area, buf, &mut app.plan_list);
+
+    let selected = app
+        .plan_list
+        .selected()
+        .map(|i| i.min(app.card.plans.len().saturating_sub(1)));
+    if let Some(plan) = selected.and_then(|i| app.card.plans.get(i)) {
+        let details = Paragraph::new(Text::from(vec![
+            Line::from(plan.venue.clone().red().bold()),
+            Line::from(""),
+            Line::from(format!("When: {}", plan.time.format("%A %-d %B, %-I:%M %p")).red()),
+            Line::from(format!("Attire: {}", plan.dress_code).red()),
+            Line::from(""),
+            Line::from(plan.description.clone().white()),
+        ]))
+        .wrap(Wrap { trim: true })
+        .block(Block::new().padding(Padding::horizontal(2)));
+        details.render(details_area, buf);
+    }
+}
+pub fn render_dateinfo_screen(area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+    let breakpoint = Breakpoint::of(area);
+    render_background(buf, app, main_chunks[0]);
+
+    app.pong_canvas(app.playground).render(app.playground, buf);
+    let Some(plan) = app.chosen_plan() else {
+        return;
+    };
+    let target_time = plan.local_time();
+    let now = app.clock.now();
     let remaining_time = if now < target_time {
         target_time - now
     } else {
@@ -370,8 +562,6 @@ fn render_dateinfo_screen(frame: &mut Frame, app: &App, main_chunks: Rc<[Rect]>)
     };
 
     let hours = remaining_time.num_hours();
-    let minutes = remaining_time.num_minutes() % 60;
-    let seconds = remaining_time.num_seconds() % 60;
 
     let mut middle_string = "";
     if hours < 1 {
@@ -382,63 +572,847 @@ fn render_dateinfo_screen(frame: &mut Frame, app: &App, main_chunks: Rc<[Rect]>)
         middle_string = "Date today!";
     }
 
-    let countdown_str = format!("{:02}:{:02}:{:02}", hours, minutes, seconds);
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .title("Countdown To Our Date...")
-        .style(Style::default().fg(Color::Red));
-
-    let time_popup_area = Rect {
-        x: area.width / 4,
-        y: area.height / 4,
-        width: area.width / 6,
-        height: area.height / 6,
+    let countdown_line = if now < target_time {
+        format!("We have {} until our date!", format_countdown(remaining_time))
+    } else {
+        middle_string = "Best. Date. Ever.";
+        format!("It's been {} since our date began!", format_elapsed(now - target_time))
     };
-    let time = Local::now().format("%H:%M:%S").to_string();
-    let time_popup = Paragraph::new(Text::from(vec![
-        Line::from(format!("We have {} until our date!", countdown_str)),
+    let time = now.format("%H:%M:%S").to_string();
+    let countdown = vec![
+        Line::from(countdown_line),
         Line::from(middle_string),
         Line::from(format!("Current Time: {}", time)),
+    ];
+
+    // on a compact terminal the countdown heads the plan instead of taking
+    // a box of its own
+    let mut lines = match breakpoint {
+        Breakpoint::Compact => {
+            let mut lines = countdown.clone();
+            lines.push(Line::from(""));
+            lines
+        }
+        _ => vec![Line::from("Our Date: ".red().bold())],
+    };
+    let time_popup = Dialog::new("Countdown")
+        .body(countdown)
+        .size(Size::Percentage(100), Size::Percentage(100))
+        .style(Style::new().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style(Style::new().white());
+
+    lines.extend([
+        Line::from(format!("Location: {} ", plan.venue).red()),
+        Line::from(format!("Time: {} ", plan.time.format("%-I:%M %p")).red()),
+        Line::from(format!("Attire: {} ", plan.dress_code).red()),
+        Line::from(""),
+    ]);
+    if let Some(answer) = app.answer.as_ref().filter(|a| a.accepted && !a.note.is_empty()) {
+        lines.push(Line::from(format!("Your note: {}", answer.note).magenta()));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from("Excited to see you my pretty lady! ".white()));
+    if breakpoint != Breakpoint::Compact {
+        lines.push(Line::from(middle_string.white()));
+    }
+    lines.push(Line::from(
+        format!("Press \'{}\' to quit!", app.card.keys.hint(Action::Quit)).white(),
+    ));
+    // the other pages, one line each or all on one line when it's cramped
+    let mut pages = Vec::new();
+    if !app.card.itinerary.is_empty() {
+        pages.push(("Press \'i\' for our itinerary", "\'i\' itinerary"));
+    }
+    if app.card.anniversary.is_some() {
+        pages.push(("Press \'a\' to count our days together", "\'a\' our days"));
+    }
+    if !app.card.letters.is_empty() {
+        pages.push(("Press \'l\' to read your letters", "\'l\' letters"));
+    }
+    match breakpoint {
+        Breakpoint::Compact if !pages.is_empty() => {
+            let short: Vec<&str> = pages.iter().map(|(_, short)| *short).collect();
+            lines.push(Line::from(short.join(", ").white()));
+        }
+        _ => lines.extend(pages.iter().map(|(long, _)| Line::from(long.white()))),
+    }
+    let wybmv = Dialog::new("Our Date:")
+        .body(lines)
+        .size(Size::Percentage(100), Size::Percentage(100))
+        .middle()
+        .style(Style::new().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style(Style::new().white());
+
+    // countdown on top, the plan under it, both clocks at the bottom when
+    // we're apart. Wide terminals put the clocks next to the plan instead
+    let main_area = main_chunks[0].union(main_chunks[1]);
+    // the clocks are the first thing to go on a really short terminal
+    let apart = app.card.long_distance.is_some()
+        && (breakpoint != Breakpoint::Compact || main_area.height >= 20);
+    let (date_area, clocks_area) = match breakpoint {
+        Breakpoint::Compact => {
+            let popup_area =
+                center(main_area, Constraint::Percentage(90), Constraint::Percentage(100));
+            let [date_area, clocks_area] = Layout::vertical([
+                Constraint::Min(0),
+                Constraint::Length(if apart { 7 } else { 0 }),
+            ])
+            .areas(popup_area);
+            (date_area, clocks_area)
+        }
+        Breakpoint::Normal => {
+            let popup_area = center(main_area, Constraint::Percentage(50), Constraint::Length(25));
+            let [time_area, date_area, clocks_area] = Layout::vertical([
+                Constraint::Length(5),
+                Constraint::Min(0),
+                Constraint::Length(if apart { 8 } else { 0 }),
+            ])
+            .areas(popup_area);
+            time_popup.render(time_area, buf);
+            (date_area, clocks_area)
+        }
+        Breakpoint::Wide => {
+            let width = if apart { 70 } else { 40 };
+            let popup_area =
+                center(main_area, Constraint::Percentage(width), Constraint::Length(25));
+            let [time_area, rest] =
+                Layout::vertical([Constraint::Length(5), Constraint::Min(0)]).areas(popup_area);
+            let [date_area, clocks_area] = Layout::horizontal([
+                Constraint::Min(0),
+                Constraint::Percentage(if apart { 50 } else { 0 }),
+            ])
+            .areas(rest);
+            time_popup.render(time_area, buf);
+            (date_area, clocks_area)
+        }
+    };
+    wybmv.render(date_area, buf);
+    if let Some(long_distance) = app.card.long_distance.as_ref().filter(|_| apart) {
+        render_long_distance(buf, clocks_area, long_distance, now, target_time);
+    }
+}
+fn render_long_distance(
+    buf: &mut Buffer,
+    area: Rect,
+    long_distance: &LongDistance,
+    now: DateTime<Local>,
+    target_time: DateTime<Local>,
+) {
+    let popup = Dialog::new(Line::from(long_distance.difference(&now)).centered())
+        .size(Size::Percentage(100), Size::Percentage(100))
+        .padding(Padding::ZERO)
+        .style(Style::new().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style(Style::new().white());
+    let inner = popup.inner(area);
+    popup.render(area, buf);
+
+    let [mine, yours] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .spacing(1)
+            .areas(inner);
+    render_city_clock(buf, mine, &long_distance.me, now, target_time);
+    render_city_clock(buf, yours, &long_distance.you, now, target_time);
+}
+fn render_city_clock(
+    buf: &mut Buffer,
+    area: Rect,
+    city: &City,
+    now: DateTime<Local>,
+    target_time: DateTime<Local>,
+) {
+    let local_now = city.at(&now);
+    let (icon, style) = if city.is_daytime(&now) {
+        ("☀", Style::new().yellow())
+    } else {
+        ("☾", Style::new().light_blue())
+    };
+    let date_line = format!("our date: {}", city.at(&target_time).format("%a %-I:%M %p"));
+    let countdown_line = if now < target_time {
+        format!("in {}", format_countdown(target_time - now))
+    } else {
+        format!("{} ago", format_elapsed(now - target_time))
+    };
+
+    // one cell per hour of the day, night hours dim, now marked with a heart
+    let hour = local_now.hour();
+    let strip: Vec<Span> = (0..24)
+        .map(|h| {
+            let day = (6..18).contains(&h);
+            match (h == hour, day) {
+                (true, _) => "♥".red().bold(),
+                (false, true) => "▀".yellow(),
+                (false, false) => "▄".blue(),
+            }
+        })
+        .collect();
+
+    let clock = Paragraph::new(Text::from(vec![
+        Line::from(vec![Span::styled(format!("{} ", icon), style), city.label.clone().bold()]),
+        Line::from(local_now.format("%H:%M:%S  %a %-d %b").to_string().white()),
+        Line::from(date_line.red()),
+        Line::from(countdown_line.red()),
+        Line::from(strip),
+    ]))
+    .centered();
+    clock.render(area, buf);
+}
+pub fn render_itinerary_screen(
+    area: Rect,
+    buf: &mut Buffer,
+    app: &mut App,
+    main_chunks: Rc<[Rect]>,
+) {
+    let breakpoint = Breakpoint::of(area);
+    render_background(buf, app, main_chunks[0]);
+
+    app.pong_canvas(app.playground).render(app.playground, buf);
+
+    let popup = Dialog::new("Itinerary")
+        .hint("press 'i' to go back")
+        .size(breakpoint.width(60), Size::Percentage(70))
+        .padding(Padding::vertical(1))
+        .style(Style::new().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style(Style::new().white());
+    let list_area = popup.inner(area);
+    popup.render(area, buf);
+
+    let now = app.clock.now();
+    let statuses = app.card.itinerary_status(now);
+    let items: Vec<ListItem> = app
+        .card
+        .itinerary
+        .iter()
+        .zip(&statuses)
+        .enumerate()
+        .map(|(i, (event, status))| {
+            let (dot, style, countdown) = match status {
+                EventStatus::Done => ("✓", Style::new().dark_gray(), "done".to_string()),
+                EventStatus::Now => ("♥", Style::new().red().bold(), "happening now!".to_string()),
+                EventStatus::Next => (
+                    "●",
+                    Style::new().magenta().bold(),
+                    format!("in {}", format_countdown(event.local_time() - now)),
+                ),
+                EventStatus::Later => (
+                    "○",
+                    Style::new().white(),
+                    format!("in {}", format_countdown(event.local_time() - now)),
+                ),
+            };
+            let rail = if i + 1 < statuses.len() { "│" } else { " " };
+            let mut lines = vec![Line::from(vec![
+                format!(" {:>8} ", event.time.format("%-I:%M %p")).into(),
+                dot.into(),
+                format!("  {}", event.name).bold(),
+                format!("   {}", countdown).italic(),
+            ])];
+            if !event.place.is_empty() {
+                lines.push(Line::from(format!("{:>10}{}  @ {}", "", rail, event.place)));
+            }
+            if !event.description.is_empty() {
+                lines.push(Line::from(format!("{:>10}{}  {}", "", rail, event.description)));
+            }
+            lines.push(Line::from(format!("{:>10}{}", "", rail)));
+            ListItem::new(lines).style(style)
+        })
+        .collect();
+    let list = List::new(items);
+    StatefulWidget::render(list, list_area, buf, &mut app.itinerary_list);
+}
+pub fn render_celebration_screen(buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+    // rings of hearts bursting out of the middle, a new ring every 20 ticks
+    let main_area = main_chunks[0].union(main_chunks[1]);
+    let colors = [Color::Red, Color::LightMagenta, Color::Magenta, Color::LightRed];
+    let (width, height) = (f64::from(main_area.width), f64::from(main_area.height) * 2.0);
+    let fireworks = Canvas::default()
+        .background_color(Color::Black)
+        .block(Block::bordered().title("Happy Valentine's Day!!!").red())
+        .marker(ratatui::symbols::Marker::Braille)
+        .x_bounds([0.0, width])
+        .y_bounds([0.0, height])
+        .paint(|ctx| {
+            for ring in 0..3u64 {
+                let age = (app.tick_count + ring * 20) % 60;
+                let spread = age as f64 * width.min(height) / 80.0;
+                for i in 0..12 {
+                    let angle = i as f64 * std::f64::consts::TAU / 12.0 + ring as f64;
+                    ctx.draw(&Heart {
+                        x: width / 2.0 + spread * angle.cos() * 1.6,
+                        y: height / 2.0 + spread * angle.sin(),
+                        radius: 0.05 + age as f64 / 300.0,
+                        color: colors[(ring as usize + i) % colors.len()],
+                        fill: Fill::Solid,
+                    });
+                }
+            }
+        });
+    fireworks.render(main_area, buf);
+
+    let popup = Dialog::new("")
+        .body(vec![
+            Line::from("IT'S TIME!!!".red().bold()),
+            Line::from(""),
+            Line::from("Our date starts right now <3".white()),
+        ])
+        .button(app.card.keys.hint(Action::Advance), "our date".dark_gray())
+        .padding(Padding::horizontal(3))
+        .style(Style::new().on_black())
+        .border_style(Style::new().red());
+    popup.render(main_area, buf);
+}
+pub fn render_anniversary_screen(
+    area: Rect,
+    buf: &mut Buffer,
+    app: &mut App,
+    main_chunks: Rc<[Rect]>,
+) {
+    let breakpoint = Breakpoint::of(area);
+    render_background(buf, app, main_chunks[0]);
+
+    app.pong_canvas(app.playground).render(app.playground, buf);
+
+    let Some(anniversary) = &app.card.anniversary else {
+        return;
+    };
+    let now = app.clock.now();
+    let today = now.date_naive();
+    let (years, months, days) = anniversary.together(today);
+
+    let mut lines = Vec::new();
+    let todays = anniversary.milestones_on(today);
+    for milestone in &todays {
+        lines.push(Line::from(anniversary.message_for(milestone).red().bold()));
+    }
+    if !todays.is_empty() {
+        lines.push(Line::from(""));
+    }
+    lines.extend([
+        Line::from(format!("{} days together", anniversary.days_together(today)).magenta().bold()),
+        Line::from(format!("{} years, {} months and {} days", years, months, days).white()),
+        Line::from(format!("since {}", anniversary.since.format("%-d %B %Y")).dark_gray()),
+        Line::from(""),
+        Line::from("Coming up:".white().bold()),
+    ]);
+    for milestone in anniversary.upcoming(today) {
+        let countdown = if milestone.date == today {
+            "today!".to_string()
+        } else {
+            format!("in {}", format_elapsed(local_time(&milestone.date.into()) - now))
+        };
+        lines.push(Line::from(vec![
+            format!("{} ", milestone.label).magenta(),
+            format!("({}) ", milestone.date.format("%-d %b %Y")).dark_gray(),
+            countdown.white(),
+        ]));
+    }
+
+    let popup = Dialog::new("Us")
+        .body(lines)
+        .hint("press 'a' to go back")
+        .size(breakpoint.width(60), Size::FitContent)
+        .padding(Padding::vertical(1))
+        .style(Style::new().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style(match (todays.is_empty(), app.offset % 2) {
+            (false, 0) => Style::new().red(),
+            _ => Style::new().white(),
+        });
+    popup.render(area, buf);
+}
+fn render_locked_screen(
+    buf: &mut Buffer,
+    app: &mut App,
+    main_chunks: Rc<[Rect]>,
+    unlock_at: DateTime<Local>,
+) {
+    let main_area = main_chunks[0].union(main_chunks[1]);
+    Block::new().on_black().render(main_area, buf);
+    render_envelope(buf, main_area, "This part is still sealed", unlock_at, app.clock.now());
+}
+fn render_envelope(
+    buf: &mut Buffer,
+    area: Rect,
+    title: &str,
+    unlock_at: DateTime<Local>,
+    now: DateTime<Local>,
+) {
+    let envelope = [
+        " ________________________ ",
+        "|\\                      /|",
+        "| \\                    / |",
+        "|  \\       <3         /  |",
+        "|   \\________________/   |",
+        "|                        |",
+        "|________________________|",
+    ];
+    let mut lines: Vec<Line> = envelope
+        .iter()
+        .map(|row| Line::from(row.replace("<3", "♥ ")).magenta())
+        .collect();
+    lines.extend([
+        Line::from(""),
+        Line::from(format!("opens {}", unlock_at.format("%a %-d %b, %-I:%M %p")).white()),
+        Line::from(format!("in {}", format_elapsed(unlock_at - now)).red().bold()),
+    ]);
+    let popup = Dialog::new(title.to_string())
+        .body(lines)
+        .padding(Padding::new(1, 1, 1, 1))
+        .style(Style::new().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style(Style::new().magenta());
+    popup.render(area, buf);
+}
+pub fn render_letters_screen(area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+    let breakpoint = Breakpoint::of(area);
+    render_background(buf, app, main_chunks[0]);
+
+    app.pong_canvas(app.playground).render(app.playground, buf);
+
+    let popup = Dialog::new("Letters")
+        .hint("up/down to pick, 'l' to go back")
+        .size(breakpoint.width(70), Size::Fixed(20))
+        .padding(Padding::ZERO)
+        .style(Style::new().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style(Style::new().white());
+    let inner = popup.inner(area);
+    popup.render(area, buf);
+
+    let [list_area, letter_area] =
+        Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .areas(inner);
+    let now = app.clock.now();
+    let items: Vec<ListItem> = app
+        .card
+        .letters
+        .iter()
+        .map(|letter| match letter.locked_until(now) {
+            Some(_) => ListItem::new(format!("✉ {}", letter.title)).dark_gray(),
+            None => ListItem::new(format!("♥ {}", letter.title)).white(),
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::new()
+                .borders(Borders::RIGHT)
+                .border_style(Style::new().magenta()),
+        )
+        .highlight_style(Style::new().black().on_magenta());
+    StatefulWidget::render(list, list_area, buf, &mut app.letter_list);
+
+    let selected = app
+        .letter_list
+        .selected()
+        .map(|i| i.min(app.card.letters.len().saturating_sub(1)));
+    let Some(letter) = selected.and_then(|i| app.card.letters.get(i)) else {
+        return;
+    };
+    match letter.locked_until(now) {
+        Some(unlock_at) => {
+            render_envelope(buf, letter_area, &letter.title, unlock_at, now);
+        }
+        None => {
+            let mut lines = vec![Line::from(letter.title.clone().red().bold()), Line::from("")];
+            lines.extend(letter.body.open().lines().map(|line| Line::from(line.to_string().white())));
+            let body = Paragraph::new(Text::from(lines))
+            .wrap(Wrap { trim: false })
+            .block(Block::new().padding(Padding::horizontal(2)));
+            body.render(letter_area, buf);
+        }
+    }
+}
+pub fn render_advent_screen(area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+    let breakpoint = Breakpoint::of(area);
+    let main_area = main_chunks[0].union(main_chunks[1]);
+    Block::new().on_black().render(main_area, buf);
+    let Some(advent) = &app.card.advent else {
+        return;
+    };
+    let days = app.card.advent_days();
+    let today = app.clock.now().date_naive();
+
+    let columns = 7;
+    let rows = days.len().div_ceil(columns) as u16;
+    let cell_width = ((main_area.width.saturating_sub(2)) / columns as u16).clamp(4, 9);
+    let grid = Dialog::new("Until our date...")
+        .hint(format!("arrows to move, {} to open", app.card.keys.hint(Action::Advance)))
+        .size(
+            Size::Fixed(cell_width * columns as u16 + 2),
+            Size::Fixed(rows * 3 + 2),
+        )
+        .padding(Padding::ZERO)
+        .style(Style::new().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style(Style::new().magenta());
+    let inner = grid.inner(main_area);
+    grid.render(main_area, buf);
+
+    for (i, day) in days.iter().enumerate() {
+        let cell = Rect {
+            x: inner.x + (i % columns) as u16 * cell_width,
+            y: inner.y + (i / columns) as u16 * 3,
+            width: cell_width,
+            height: 3,
+        }
+        .intersection(inner);
+        let last = i + 1 == days.len();
+        let (mark, style) = if app.saved.opened_days.contains(day) {
+            ("♥", Style::new().red())
+        } else if *day <= today {
+            (if last { "?!" } else { "?" }, Style::new().magenta().bold())
+        } else {
+            ("·", Style::new().dark_gray())
+        };
+        let style = if i == app.advent_cursor {
+            style.reversed()
+        } else {
+            style
+        };
+        let label = Paragraph::new(Line::from(format!("{} {}", day.format("%-d"), mark)))
+            .centered()
+            .style(style)
+            .block(Block::bordered().border_style(style));
+        label.render(cell, buf);
+    }
+
+    let Some(open) = app.advent_open else {
+        return;
+    };
+    let surprise = advent.surprise(open);
+    let kind = surprise.map_or(SurpriseKind::Message, |s| s.kind);
+    let title = surprise
+        .map(|s| s.title.clone())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| days[open].format("%A %-d %B").to_string());
+    let text = surprise.map_or("A little love for today <3".to_string(), |s| s.text.open());
+
+    let popup = Dialog::new(title)
+        .hint(format!("press {}", app.card.keys.hint(Action::Advance)))
+        .size(breakpoint.width(60), Size::Percentage(60))
+        .padding(Padding::uniform(1))
+        .style(Style::new().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style(Style::new().magenta());
+    let inner = popup.inner(main_area);
+    popup.render(main_area, buf);
+
+    let text_style = match kind {
+        SurpriseKind::Poem => Style::new().white().italic(),
+        _ => Style::new().white(),
+    };
+    let lines: Vec<Line> = text
+        .lines()
+        .map(|line| Line::from(line.to_string()).style(text_style))
+        .collect();
+    let text_area = if kind == SurpriseKind::Animation {
+        let [animation_area, text_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(lines.len() as u16)])
+                .areas(inner);
+        let (width, height) = (
+            f64::from(animation_area.width),
+            f64::from(animation_area.height) * 2.0,
+        );
+        let heart = Heart {
+            x: width / 2.0,
+            y: height / 2.0,
+            radius: height / 40.0 * heartbeat(app.tick_count),
+            color: Color::LightRed,
+            fill: Fill::Gradient(Color::Rgb(120, 0, 30)),
+        };
+        let canvas = Canvas::default()
+            .background_color(Color::Black)
+            .marker(ratatui::symbols::Marker::Braille)
+            .x_bounds([0.0, width])
+            .y_bounds([0.0, height])
+            .paint(|ctx| ctx.draw(&heart));
+        canvas.render(animation_area, buf);
+        text_area
+    } else {
+        inner
+    };
+    let body = Paragraph::new(Text::from(lines)).wrap(Wrap { trim: false });
+    let body = match kind {
+        SurpriseKind::Message => body,
+        _ => body.centered(),
+    };
+    body.render(text_area, buf);
+}
+pub fn render_gate_screen(area: Rect, buf: &mut Buffer, app: &mut App, main_chunks: Rc<[Rect]>) {
+    let breakpoint = Breakpoint::of(area);
+    let main_area = main_chunks[0].union(main_chunks[1]);
+    Block::new().on_black().render(main_area, buf);
+    let Some(gate) = &app.card.gate else {
+        return;
+    };
+    let attempts_left = app.gate_attempts_left();
+    let hints = gate.hints_after(app.saved.gate_attempts);
+
+    let popup = Dialog::new("Before we begin...")
+        .hint("enter to answer, esc to leave")
+        .size(breakpoint.width(60), Size::Fixed(hints.len() as u16 + 12))
+        .padding(Padding::horizontal(2))
+        .style(Style::new().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style(Style::new().magenta());
+    let inner = popup.inner(main_area);
+    popup.render(main_area, buf);
+
+    let [question_area, input_area, status_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Min(0),
+    ])
+    .areas(inner);
+    let question = Paragraph::new(Text::from(vec![
+        Line::from(""),
+        Line::from(gate.question.clone().white().bold()),
     ]))
     .wrap(Wrap { trim: true })
-    .on_black()
-    .alignment(Alignment::Center)
-    .centered()
-    .block(
-        Block::new()
-            .title("Countdown")
-            .title_style(Style::new().white().bold())
-            .borders(Borders::ALL)
-            .border_style(Style::new().white()),
-    );
-
-    let popup_area = center(
-        frame.area(),
-        Constraint::Percentage(50),
-        Constraint::Length(25),
-    );
-    frame.render_widget(Clear, popup_area);
-    frame.render_widget(time_popup, popup_area);
-    let wybmv = Paragraph::new(Text::from(vec![
-        Line::from("Our Date: ".red().bold()),
-        Line::from("Location: J Wilson's ".red()),
-        Line::from("Time: 4:00 PM ".red()),
-        Line::from("Attire: Something sexy ".red()),
-        Line::from(" ".red()),
-        Line::from("Excited to see you my pretty lady! ".white()),
-        Line::from(middle_string.white()),
-        Line::from("Press \'q\' to quit!".white()),
+    .centered();
+    question.render(question_area, buf);
+
+    if attempts_left == 0 {
+        let locked = Paragraph::new(Text::from(vec![
+            Line::from("Out of guesses!".red().bold()),
+            Line::from("Come find me and I'll let you in <3".white()),
+        ]))
+        .centered();
+        locked.render(input_area.union(status_area), buf);
+        return;
+    }
+
+    let input = TextInputWidget::new(&app.gate_input)
+        .style(Style::new().white())
+        .placeholder("your answer...".dark_gray().italic())
+        .block(
+            Block::bordered()
+                .border_style(Style::new().magenta())
+                .padding(Padding::horizontal(1)),
+        );
+    StatefulWidget::render(input, input_area, buf, &mut app.gate_input_state);
+    if let Some(cursor) = app.gate_input_state.cursor {
+        app.cursor = Some(cursor);
+    }
+
+    let mut lines = Vec::new();
+    if app.saved.gate_attempts > 0 {
+        lines.push(Line::from(
+            format!("Not quite! {} guesses left", attempts_left).red(),
+        ));
+    }
+    for hint in hints {
+        lines.push(Line::from(format!("hint: {}", hint).magenta().italic()));
+    }
+    let status = Paragraph::new(Text::from(lines)).wrap(Wrap { trim: true }).centered();
+    status.render(status_area, buf);
+}
+
+pub fn render_unlock(frame: &mut Frame, prompt: &mut Unlock) {
+    let area = frame.area();
+    if too_small(area) {
+        render_too_small(area, frame.buffer_mut());
+        return;
+    }
+    let breakpoint = Breakpoint::of(area);
+    frame.render_widget(Block::new().on_black(), area);
+
+    let popup = Dialog::new("This card is locked")
+        .hint("enter to unlock, esc to leave")
+        .size(breakpoint.width(60), Size::Fixed(11))
+        .padding(Padding::horizontal(2))
+        .style(Style::new().on_black())
+        .title_style(Style::new().white().bold())
+        .border_style(Style::new().magenta());
+    let inner = popup.inner(area);
+    frame.render_widget(popup, area);
+
+    let [question_area, input_area, status_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(3),
+        Constraint::Min(0),
+    ])
+    .areas(inner);
+    let question = Paragraph::new(Text::from(vec![
+        Line::from(""),
+        Line::from("What's the secret word?".white().bold()),
+        Line::from(prompt.file_name.clone().dark_gray()),
     ]))
-    .wrap(Wrap { trim: false })
-    .alignment(Alignment::Center)
-    .centered()
-    .block(
-        Block::new()
-            .title("Our Date:")
-            .title_style(Style::new().white().bold())
-            .borders(Borders::ALL)
-            .padding(Padding::new(0, 0, popup_area.height / 4, 0))
-            .border_style(Style::new().white()),
-    );
-    frame.render_widget(wybmv, popup_area);
+    .centered();
+    frame.render_widget(question, question_area);
+
+    let input = TextInputWidget::new(&prompt.input)
+        .style(Style::new().white())
+        .placeholder("passphrase".dark_gray().italic())
+        .mask('•')
+        .block(
+            Block::bordered()
+                .border_style(Style::new().magenta())
+                .padding(Padding::horizontal(1)),
+        );
+    frame.render_stateful_widget(input, input_area, &mut prompt.input_state);
+    if let Some(cursor) = prompt.input_state.cursor {
+        frame.set_cursor_position(cursor);
+    }
+
+    let status = if prompt.checking {
+        Line::from("Unlocking...".magenta().italic())
+    } else if prompt.wrong > 0 {
+        Line::from("That's not it, try again".red())
+    } else {
+        Line::from("")
+    };
+    frame.render_widget(Paragraph::new(status).centered(), status_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Answer;
+    use crate::card::Card;
+    use crate::clock::Clock;
+    use crate::widget::CardWidget;
+    use chrono::NaiveDate;
+    use ratatui::{backend::TestBackend, Terminal};
+    use std::path::Path;
+
+    // just under the minimum, the minimum, and one of each breakpoint
+    const SIZES: [(u16, u16); 5] = [(39, 17), (40, 18), (80, 24), (120, 40), (200, 60)];
+
+    // Each screen, the title of its popup and something from the bottom of
+    // it that goes missing first when it doesn't fit.
+    const POPUPS: [(CurrentScreen, &str, &str); 11] = [
+        (CurrentScreen::Gate, "Before we begin...", "enter to answer"),
+        (CurrentScreen::Advent, "Until our date...", "to open"),
+        (CurrentScreen::WillYouBeMyValentine, "To my pretty lady:", "Yes"),
+        (CurrentScreen::YesValentine, "Lucky meeeee", "Show me!"),
+        (CurrentScreen::NotValentine, "FUCK YOUUUUU", "try again"),
+        (CurrentScreen::Rsvp, "RSVP", "esc to skip"),
+        (CurrentScreen::DateChoice, "Pick our date:", "to pick"),
+        (CurrentScreen::DateInfo, "Our Date:", "to quit!"),
+        (CurrentScreen::Itinerary, "Itinerary", "to go back"),
+        (CurrentScreen::Anniversary, "Us", "to go back"),
+        (CurrentScreen::Letters, "Letters", "to go back"),
+    ];
+
+    fn app(screen: CurrentScreen) -> App {
+        let card = Card::parse(include_str!("../card.example.toml"), Path::new("card.toml"))
+            .unwrap();
+        // the day before the date, with everything unlocked but the last letter
+        let now = NaiveDate::from_ymd_opt(2025, 2, 14).unwrap().and_hms_opt(12, 0, 0).unwrap();
+        let mut app = App::new(card).with_clock(Clock::starting_at(local_time(&now)));
+        app.answer = Some(Answer {
+            accepted: true,
+            plan: Some(0),
+            ..Answer::default()
+        });
+        app.current_screen = screen;
+        app
+    }
+
+    fn draw(app: &mut App, (width, height): (u16, u16)) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|frame| frame.render_stateful_widget(CardWidget, frame.area(), app))
+            .unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    fn rows(buf: &Buffer) -> Vec<String> {
+        (0..buf.area.height)
+            .map(|y| (0..buf.area.width).map(|x| buf[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    fn symbol(buf: &Buffer, x: u16, y: u16) -> &str {
+        buf.cell((x, y)).map_or("", |cell| cell.symbol())
+    }
+
+    // The popup with `title` along its top border, if all four of its
+    // corners made it onto the screen.
+    fn popup(buf: &Buffer, title: &str) -> Option<Rect> {
+        let width = title.chars().count() as u16;
+        for y in 0..buf.area.height {
+            for x in 1..buf.area.width.saturating_sub(width) {
+                let found = title
+                    .chars()
+                    .enumerate()
+                    .all(|(i, c)| symbol(buf, x + i as u16, y) == c.to_string());
+                if !found || symbol(buf, x - 1, y) != "┌" {
+                    continue;
+                }
+                let mut right = x + width;
+                while symbol(buf, right, y) == "─" {
+                    right += 1;
+                }
+                if symbol(buf, right, y) != "┐" {
+                    continue;
+                }
+                let mut bottom = y + 1;
+                while symbol(buf, right, bottom) == "│" {
+                    bottom += 1;
+                }
+                if symbol(buf, right, bottom) == "┘" && symbol(buf, x - 1, bottom) == "└" {
+                    return Some(Rect::new(x - 1, y, right - x + 2, bottom - y + 1));
+                }
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn asks_for_a_bigger_terminal_below_the_minimum() {
+        let needs = format!("the card needs {}x{}", MIN_WIDTH, MIN_HEIGHT);
+        for size in [(39, 17), (MIN_WIDTH - 1, MIN_HEIGHT), (MIN_WIDTH, MIN_HEIGHT - 1)] {
+            let buf = draw(&mut app(CurrentScreen::WillYouBeMyValentine), size);
+            let text = rows(&buf).concat();
+            assert!(text.contains(&needs), "{:?}", size);
+        }
+        for &size in &SIZES[1..] {
+            let buf = draw(&mut app(CurrentScreen::WillYouBeMyValentine), size);
+            let text = rows(&buf).concat();
+            assert!(!text.contains(&needs), "{:?}", size);
+        }
+    }
+
+    #[test]
+    fn sizes_cover_every_breakpoint() {
+        let breakpoints: Vec<Breakpoint> =
+            SIZES[1..].iter().map(|&(w, h)| Breakpoint::of(Rect::new(0, 0, w, h))).collect();
+        for breakpoint in [Breakpoint::Compact, Breakpoint::Normal, Breakpoint::Wide] {
+            assert!(breakpoints.contains(&breakpoint), "{:?}", breakpoint);
+        }
+    }
+
+    #[test]
+    fn every_popup_fits_at_every_size() {
+        let mut cut_off = Vec::new();
+        for &size in &SIZES[1..] {
+            for (screen, title, bottom) in POPUPS.iter().cloned() {
+                let buf = draw(&mut app(screen.clone()), size);
+                let rows = rows(&buf);
+                if popup(&buf, title).is_none() || !rows.iter().any(|row| row.contains(bottom)) {
+                    cut_off.push(format!("{:?} at {:?}:\n{}", screen, size, rows.join("\n")));
+                }
+            }
+        }
+        assert!(cut_off.is_empty(), "{}", cut_off.join("\n\n"));
+    }
+
+    #[test]
+    fn an_open_advent_day_and_the_celebration_fit_at_every_size() {
+        for &size in &SIZES[1..] {
+            let mut advent = app(CurrentScreen::Advent);
+            advent.advent_open = Some(0);
+            let buf = draw(&mut advent, size);
+            assert!(popup(&buf, "One week to go").is_some(), "{:?}", size);
+
+            let buf = draw(&mut app(CurrentScreen::Celebration), size);
+            let text = rows(&buf).concat();
+            assert!(text.contains("IT'S TIME!!!") && text.contains("our date"), "{:?}", size);
+        }
+    }
 }
@@ -0,0 +1,78 @@
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::DefaultTerminal;
+use std::{io, path::Path};
+use zeroize::Zeroizing;
+
+use crate::input::{TextInput, TextInputState};
+use crate::ui::render_unlock;
+use crate::vault::Locked;
+
+// The passphrase prompt shown before an encrypted card is opened. It runs on
+// its own, before the App exists, so nothing from the card is around until
+// the passphrase is right.
+#[derive(Debug)]
+pub struct Unlock {
+    pub file_name: String,
+    pub input: TextInput,
+    pub input_state: TextInputState,
+    pub wrong: u32,
+    pub checking: bool,
+}
+
+impl Unlock {
+    fn new(path: &Path) -> Self {
+        Self {
+            file_name: path
+                .file_name()
+                .map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into_owned()),
+            input: TextInput::new().with_max_len(200),
+            input_state: TextInputState::default(),
+            wrong: 0,
+            checking: false,
+        }
+    }
+}
+
+// leaving with Esc shouldn't leave a half typed passphrase behind either
+impl Drop for Unlock {
+    fn drop(&mut self) {
+        self.input.wipe();
+    }
+}
+
+// Asks until the passphrase opens the card. None when they leave instead.
+pub fn unlock(
+    terminal: &mut DefaultTerminal,
+    path: &Path,
+    locked: &Locked,
+) -> io::Result<Option<Zeroizing<String>>> {
+    let mut prompt = Unlock::new(path);
+    loop {
+        terminal.draw(|frame| render_unlock(frame, &mut prompt))?;
+        match event::read()? {
+            Event::Key(key) if key.kind != KeyEventKind::Release => match key.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(None)
+                }
+                KeyCode::Enter if !prompt.input.is_empty() => {
+                    // the key derivation takes a moment, say so
+                    prompt.checking = true;
+                    terminal.draw(|frame| render_unlock(frame, &mut prompt))?;
+                    let passphrase = Zeroizing::new(prompt.input.value().to_owned());
+                    prompt.input.wipe();
+                    prompt.checking = false;
+                    match locked.open(&passphrase)? {
+                        Some(text) => return Ok(Some(text)),
+                        None => prompt.wrong += 1,
+                    }
+                }
+                _ => {
+                    prompt.input.handle_key(key);
+                }
+            },
+            Event::Paste(text) => prompt.input.insert_str(&text),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use std::io;
+use zeroize::Zeroizing;
+
+// An encrypted card file is plain text so it survives being pasted into a
+// message:
+//
+//   valentines_rs encrypted card v1
+//   argon2id m=19456 t=2 p=1
+//   <salt, base64>
+//   <nonce + ciphertext, base64 over as many lines as it takes>
+//
+// The key comes from the passphrase through Argon2id with the costs on the
+// second line, the card itself is sealed with XChaCha20-Poly1305 and the two
+// header lines are authenticated along with it.
+const MAGIC: &str = "valentines_rs encrypted card v1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const LINE_WIDTH: usize = 64;
+
+// OWASP's minimum for Argon2id: 19 MiB, two passes.
+pub const MEMORY_KIB: u32 = 19 * 1024;
+pub const PASSES: u32 = 2;
+pub const LANES: u32 = 1;
+// Refuse files asking for more than this, rather than eating all the memory.
+const MAX_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_PASSES: u32 = 16;
+
+pub fn is_encrypted(text: &str) -> bool {
+    text.lines().next().is_some_and(|line| line.trim() == MAGIC)
+}
+
+#[derive(Debug)]
+pub struct Locked {
+    params: Params,
+    header: String,
+    salt: Vec<u8>,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+impl Locked {
+    // Reads the envelope without needing the passphrase, so a damaged file is
+    // reported before anyone is asked for it.
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        if lines.next() != Some(MAGIC) {
+            return Err(damaged("not an encrypted card"));
+        }
+        let kdf = lines.next().ok_or_else(|| damaged("missing key settings"))?;
+        let params = parse_params(kdf)?;
+        let salt = lines
+            .next()
+            .and_then(|line| STANDARD.decode(line).ok())
+            .filter(|salt| salt.len() >= SALT_LEN)
+            .ok_or_else(|| damaged("bad salt"))?;
+        let body: String = lines.collect();
+        let mut body = STANDARD
+            .decode(body)
+            .map_err(|_| damaged("bad ciphertext"))?;
+        if body.len() < NONCE_LEN {
+            return Err(damaged("ciphertext too short"));
+        }
+        let ciphertext = body.split_off(NONCE_LEN);
+        Ok(Self {
+            params,
+            header: format!("{MAGIC}\n{kdf}"),
+            salt,
+            nonce: body,
+            ciphertext,
+        })
+    }
+
+    // None when the passphrase is wrong, or the file was changed since it was
+    // encrypted; the two can't be told apart.
+    pub fn open(&self, passphrase: &str) -> io::Result<Option<Zeroizing<String>>> {
+        let key = derive_key(passphrase, &self.salt, self.params.clone())?;
+        let cipher = XChaCha20Poly1305::new(key.as_slice().into());
+        let payload = Payload {
+            msg: &self.ciphertext,
+            aad: self.header.as_bytes(),
+        };
+        let Ok(plain) = cipher.decrypt(XNonce::from_slice(&self.nonce), payload) else {
+            return Ok(None);
+        };
+        let plain = Zeroizing::new(plain);
+        let text = std::str::from_utf8(&plain).map_err(|_| damaged("card is not UTF-8"))?;
+        Ok(Some(Zeroizing::new(text.to_owned())))
+    }
+}
+
+pub fn encrypt(text: &str, passphrase: &str) -> io::Result<String> {
+    let params = Params::new(MEMORY_KIB, PASSES, LANES, Some(32)).map_err(kdf_error)?;
+    let kdf = format!("argon2id m={MEMORY_KIB} t={PASSES} p={LANES}");
+    let header = format!("{MAGIC}\n{kdf}");
+
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    getrandom::getrandom(&mut salt).map_err(|err| io::Error::other(err.to_string()))?;
+    getrandom::getrandom(&mut nonce).map_err(|err| io::Error::other(err.to_string()))?;
+
+    let key = derive_key(passphrase, &salt, params)?;
+    let cipher = XChaCha20Poly1305::new(key.as_slice().into());
+    let payload = Payload {
+        msg: text.as_bytes(),
+        aad: header.as_bytes(),
+    };
+    let ciphertext = cipher
+        .encrypt(XNonce::from_slice(&nonce), payload)
+        .map_err(|_| io::Error::other("encryption failed"))?;
+
+    let mut body = nonce.to_vec();
+    body.extend(ciphertext);
+    let body = STANDARD.encode(body);
+    let mut out = format!("{header}\n{}\n", STANDARD.encode(salt));
+    for chunk in body.as_bytes().chunks(LINE_WIDTH) {
+        // base64 is ASCII, so any chunk is valid UTF-8
+        out.push_str(std::str::from_utf8(chunk).unwrap());
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: Params) -> io::Result<Zeroizing<[u8; 32]>> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(kdf_error)?;
+    Ok(key)
+}
+
+// "argon2id m=19456 t=2 p=1", in any order.
+fn parse_params(line: &str) -> io::Result<Params> {
+    let mut words = line.split_whitespace();
+    if words.next() != Some("argon2id") {
+        return Err(damaged("unknown key derivation"));
+    }
+    let (mut m, mut t, mut p) = (None, None, None);
+    for word in words {
+        let (name, value) = word.split_once('=').ok_or_else(|| damaged("bad key settings"))?;
+        let value: u32 = value.parse().map_err(|_| damaged("bad key settings"))?;
+        match name {
+            "m" => m = Some(value),
+            "t" => t = Some(value),
+            "p" => p = Some(value),
+            _ => return Err(damaged("bad key settings")),
+        }
+    }
+    match (m, t, p) {
+        (Some(m), Some(t), _) if m > MAX_MEMORY_KIB || t > MAX_PASSES => {
+            Err(damaged("key settings are too expensive"))
+        }
+        (Some(m), Some(t), Some(p)) => Params::new(m, t, p, Some(32)).map_err(kdf_error),
+        _ => Err(damaged("bad key settings")),
+    }
+}
+
+fn damaged(reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("encrypted card is damaged: {reason}"),
+    )
+}
+
+fn kdf_error(err: argon2::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("key derivation: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARD: &str = "[card]\nto = \"Sam\"\n";
+
+    // the salt line and the base64 body, so tests can rebuild the file
+    fn split(text: &str) -> (Vec<&str>, Vec<u8>) {
+        let lines: Vec<&str> = text.lines().collect();
+        let body = STANDARD.decode(lines[3..].concat()).unwrap();
+        (lines[..3].to_vec(), body)
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = encrypt(CARD, "hunter2").unwrap();
+        assert!(is_encrypted(&text));
+        let locked = Locked::parse(&text).unwrap();
+        assert_eq!(locked.open("hunter2").unwrap().as_deref().map(String::as_str), Some(CARD));
+    }
+
+    #[test]
+    fn wrong_passphrase() {
+        let locked = Locked::parse(&encrypt(CARD, "hunter2").unwrap()).unwrap();
+        assert!(locked.open("hunter3").unwrap().is_none());
+        assert!(locked.open("").unwrap().is_none());
+    }
+
+    #[test]
+    fn changed_header_is_rejected() {
+        // same costs written in another order: parses fine, but the header is
+        // authenticated as written
+        let text = encrypt(CARD, "hunter2").unwrap();
+        let text = text.replacen(
+            &format!("m={MEMORY_KIB} t={PASSES}"),
+            &format!("t={PASSES} m={MEMORY_KIB}"),
+            1,
+        );
+        let locked = Locked::parse(&text).unwrap();
+        assert!(locked.open("hunter2").unwrap().is_none());
+    }
+
+    #[test]
+    fn flipped_byte_is_rejected() {
+        let text = encrypt(CARD, "hunter2").unwrap();
+        let (head, mut body) = split(&text);
+        let last = body.len() - 1;
+        body[NONCE_LEN] ^= 1;
+        let flipped = format!("{}\n{}\n", head.join("\n"), STANDARD.encode(&body));
+        assert!(Locked::parse(&flipped).unwrap().open("hunter2").unwrap().is_none());
+
+        // the tag at the end too
+        body[NONCE_LEN] ^= 1;
+        body[last] ^= 0x80;
+        let flipped = format!("{}\n{}\n", head.join("\n"), STANDARD.encode(&body));
+        assert!(Locked::parse(&flipped).unwrap().open("hunter2").unwrap().is_none());
+    }
+
+    #[test]
+    fn expensive_params_are_refused() {
+        let text = encrypt(CARD, "hunter2").unwrap();
+        let kdf = format!("argon2id m={MEMORY_KIB} t={PASSES} p={LANES}");
+        for costly in [
+            format!("argon2id m={} t=2 p=1", MAX_MEMORY_KIB + 1),
+            format!("argon2id m=19456 t={} p=1", MAX_PASSES + 1),
+        ] {
+            let err = Locked::parse(&text.replacen(&kdf, &costly, 1)).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert!(err.to_string().contains("too expensive"), "{err}");
+        }
+        // and the caps themselves are fine
+        let at_cap = format!("argon2id m={MAX_MEMORY_KIB} t={MAX_PASSES} p=1");
+        assert!(Locked::parse(&text.replacen(&kdf, &at_cap, 1)).is_ok());
+    }
+
+    #[test]
+    fn not_a_card() {
+        assert!(!is_encrypted("[card]\n"));
+        assert!(Locked::parse("[card]\n").is_err());
+        assert!(Locked::parse(MAGIC).is_err());
+    }
+}
@@ -0,0 +1,37 @@
+use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+
+use crate::app::App;
+use crate::ui::ui;
+
+// The whole card, for drawing inside another ratatui app in any area. The
+// App is its state: hand it every event with App::handle_event, call
+// App::on_tick every TICK_RATE, and after drawing put the terminal cursor
+// at App::cursor if there is one. App::dirty says whether drawing again
+// would show anything new, and App::exit goes true when they quit. Once
+// any scenes of their own are added, App::check_screens says if the card
+// names a screen that isn't there.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CardWidget;
+
+impl StatefulWidget for CardWidget {
+    type State = App;
+
+    fn render(self, area: Rect, buf: &mut Buffer, app: &mut App) {
+        // scenes hear about the screen they're on before it's first drawn
+        app.settle();
+        app.cursor = None;
+        app.dirty = false;
+        app.drawn_second = app.clock.now().timestamp();
+        ui(area, buf, app);
+        if let Some(transition) = &app.transition {
+            transition.blend(area, buf);
+        }
+        // kept as shown, so a transition that interrupts another one picks
+        // up from where it was
+        let mut last_frame = Buffer::empty(area);
+        for (position, cell) in area.positions().zip(last_frame.content.iter_mut()) {
+            *cell = buf[position].clone();
+        }
+        app.last_frame = last_frame;
+    }
+}